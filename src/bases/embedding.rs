@@ -0,0 +1,151 @@
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct SectorEmbedding{
+    pub egn_v : EigenNumMomentum,
+    pub length : usize,
+    // light basis of BasisNK : (representative, length) and configuration -> (index, translation)
+    basis : Vec<(usize, usize)>,
+    indices : FnvHashMap<usize, (usize, usize)>,
+}
+
+impl SectorEmbedding{
+    pub fn new(egn_v : EigenNumMomentum, length : usize) -> Result<Self, Error>{
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light()?;
+        Ok(Self::from_light(egn_v, length, basis, indices))
+    }
+
+    pub fn from_light(egn_v : EigenNumMomentum, length : usize, basis : Vec<(usize, usize)>, indices : FnvHashMap<usize, (usize, usize)>) -> Self{
+        Self{
+            egn_v,
+            length,
+            basis,
+            indices,
+        }
+    }
+
+    pub fn dim(&self) -> usize{
+        self.basis.len()
+    }
+
+    pub fn full_dim(&self) -> usize{
+        1 << self.length
+    }
+
+    pub fn amplitude(&self, config : usize) -> Option<(usize, Complex64)>{
+        // <config|rep~> = omega_k^{-d} / sqrt(p) for config = cyclic_move^d(rep), the normalization of
+        // the basis used by sector_hamiltonian (normalize_factor is sqrt(p) / L per translation)
+        let &(idx, d) = self.indices.get(&config)?;
+        let period = period_unsafe(self.basis[idx].0, self.length) as f64;
+        let phase = self.egn_v.phase_factor(self.length).inv().powu(d as u32);
+        Some((idx, phase / period.sqrt()))
+    }
+
+    pub fn to_sparse(&self, v : &Array1<Complex64>) -> Result<Vec<(usize, Complex64)>, Error>{
+        // nonzero amplitudes on configurations, sorted by configuration
+        if v.len() != self.dim(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let mut result : Vec<(usize, Complex64)> = self.indices.keys()
+                        .map(|&config| {
+                            let (idx, amp) = self.amplitude(config).unwrap();
+                            (config, v[idx] * amp)
+                        })
+                        .collect();
+        result.sort_by_key(|x| x.0);
+        return Ok(result);
+    }
+
+    pub fn to_full(&self, v : &Array1<Complex64>) -> Result<Array1<Complex64>, Error>{
+        let mut result : Array1<Complex64> = Array1::zeros(self.full_dim());
+        for (config, x) in self.to_sparse(v)?{
+            result[config] = x;
+        }
+        return Ok(result);
+    }
+
+    pub fn project_sparse(&self, entries : &[(usize, Complex64)]) -> Array1<Complex64>{
+        // <rep~|psi> for psi given by (configuration, amplitude), configurations out of the sector are ignored
+        let mut result : Array1<Complex64> = Array1::zeros(self.dim());
+        for &(config, x) in entries.iter(){
+            if let Some((idx, amp)) = self.amplitude(config){
+                result[idx] += amp.conj() * x;
+            }
+        }
+        return result;
+    }
+
+    pub fn project(&self, psi : &Array1<Complex64>) -> Result<Array1<Complex64>, Error>{
+        if psi.len() != self.full_dim(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let mut result : Array1<Complex64> = Array1::zeros(self.dim());
+        for (&config, &(idx, _)) in self.indices.iter(){
+            result[idx] += self.amplitude(config).unwrap().1.conj() * psi[config];
+        }
+        return Ok(result);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn apply_full<H : HamiltonianTerm>(h : &H, psi : &Array1<Complex64>, length : usize) -> Array1<Complex64>{
+        let mut result : Array1<Complex64> = Array1::zeros(psi.len());
+        for (config, &x) in psi.iter().enumerate(){
+            for (config2, value) in h.elements(config, length){
+                result[config2] += value * x;
+            }
+        }
+        result
+    }
+
+    #[test]
+    fn test_embedding(){
+        let length = 8;
+        let egn_v = EigenNumMomentum::new(3, 1);
+        let xxz = PeriodicNearestXXZ::new(1f64, 0.5f64);
+        let embedding = SectorEmbedding::new(egn_v, length).unwrap();
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let (eval, evec) = sector_hamiltonian(&basis, &indices, egn_v, &xxz).eigh(UPLO::Lower).unwrap();
+
+        for i in 0..embedding.dim(){
+            let v = evec.column(i).to_owned();
+            let full = embedding.to_full(&v).unwrap();
+            // normalized eigenvector of the full Hamiltonian
+            assert!((full.iter().map(|x| x.norm_sqr()).sum::<f64>() - 1f64).abs() < 1e-10);
+            let residual = apply_full(&xxz, &full, length) - &full * Complex64::from(eval[i]);
+            assert!(residual.iter().map(|x| x.norm()).fold(0f64, f64::max) < 1e-10);
+            // uniform local S^z = m / L - 1/2
+            for site in 0..length{
+                let sz : f64 = full.iter().enumerate().map(|(c, x)| x.norm_sqr() * (((c >> site) & 1) as f64 - 0.5)).sum();
+                assert!((sz - (3f64 / 8f64 - 0.5)).abs() < 1e-10);
+            }
+
+            let back = embedding.project(&full).unwrap();
+            assert!(back.iter().zip(v.iter()).all(|(x, y)| (x - y).norm() < 1e-12));
+            let back = embedding.project_sparse(&embedding.to_sparse(&v).unwrap());
+            assert!(back.iter().zip(v.iter()).all(|(x, y)| (x - y).norm() < 1e-12));
+
+            // other momentum sectors are orthogonal
+            let other = SectorEmbedding::new(EigenNumMomentum::new(3, 2), length).unwrap();
+            assert!(other.project(&full).unwrap().iter().all(|x| x.norm() < 1e-12));
+        }
+        assert!(embedding.to_full(&Array1::zeros(1)).is_err());
+    }
+
+    #[test]
+    fn test_projection(){
+        // projecting a random state and embedding back is a projector
+        let length = 6;
+        let embedding = SectorEmbedding::new(EigenNumMomentum::new(2, 3), length).unwrap();
+        let psi = random_state(1 << length, &mut rng_seed(3));
+        let once = embedding.to_full(&embedding.project(&psi).unwrap()).unwrap();
+        let twice = embedding.to_full(&embedding.project(&once).unwrap()).unwrap();
+        assert!(once.iter().zip(twice.iter()).all(|(x, y)| (x - y).norm() < 1e-12));
+        let overlap : Complex64 = psi.iter().zip(once.iter()).map(|(x, y)| x.conj() * y).sum();
+        assert!((overlap.re - once.iter().map(|x| x.norm_sqr()).sum::<f64>()).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{prelude::*, states::hubbard::{split_species, is_fermion_rep, fermion_period, fermion_cyclic_move}};
+
+pub type BasisHubbard = BasisGenerator<EigenSpinNumMomentum>;
+
+impl BasisHubbard{
+    pub fn new(v : EigenSpinNumMomentum, length : usize) -> Self{
+        // length : number of sites
+        Self{
+            length,
+            value : Box::new(v),
+        }
+    }
+
+    pub fn length(&self) -> usize{
+        self.length
+    }
+
+    pub fn value(&self) -> EigenSpinNumMomentum{
+        *self.value
+    }
+
+    pub fn build(&self) -> Result<(Vec<HubbardMomentumState>, FnvHashMap<RepSpinNumMomentum, (usize, usize)>), Error>{
+        let eigen_v = &self.value;
+        if eigen_v.up_number() > self.length || eigen_v.down_number() > self.length{
+            return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+        }
+
+        let max_state = 1 << (2 * self.length);
+        let mut basis : Vec<HubbardMomentumState> = Vec::new();
+        let mut indices : FnvHashMap<RepSpinNumMomentum, (usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..max_state{
+            if let Some(state) = HubbardMomentumState::new(n, self.length, eigen_v){
+                for (num, (i, _coeff)) in state.state.iter(){
+                    indices.insert(Representation(**eigen_v, *num), (idx, *i));
+                }
+
+                basis.push(state);
+                idx += 1;
+            }
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+
+    pub fn build_light(&self) -> Result<(Vec<(usize, usize)>, FnvHashMap<usize, (usize, usize)>), Error>{
+        let length = self.length;
+        let eigen_v = &self.value;
+        if eigen_v.up_number() > length || eigen_v.down_number() > length{
+            return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+        }
+
+        let max_state = 1 << (2 * length);
+        let mut basis : Vec<(usize, usize)> = Vec::new();
+        let mut indices : FnvHashMap<usize, (usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..max_state{
+            let (up, down) = split_species(n, length);
+            if up.count_ones() as usize != eigen_v.up_number()
+                || down.count_ones() as usize != eigen_v.down_number()
+                || !is_fermion_rep(n, length){
+                continue;
+            }
+
+            let (period, sign) = fermion_period(n, length);
+            if !eigen_v.check_fermion_commensurability(period, sign, length){
+                continue;
+            }
+
+            let mut temp = n;
+            for i in 0..period{
+                indices.insert(temp, (idx, i));
+                temp = fermion_cyclic_move(temp, length).0;
+            }
+
+            basis.push((n, length));
+            idx += 1;
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::states::hubbard::join_species;
+
+    #[test]
+    fn test_basis_hubbard(){
+        let length = 4;
+        let mut total = 0;
+        for k in 0..length{
+            let gen = BasisHubbard::new(EigenSpinNumMomentum::new(2, 1, k), length);
+            let (basis, indices) = gen.build_light().unwrap();
+            let (heavy, heavy_indices) = gen.build().unwrap();
+            assert_eq!(basis.len(), heavy.len());
+            assert_eq!(indices.len(), heavy_indices.len());
+            total += basis.len();
+        }
+        // C(4, 2) * C(4, 1) configurations in total
+        assert_eq!(total, 24);
+
+        let gen = BasisHubbard::new(EigenSpinNumMomentum::new(2, 0, 0), length);
+        let (basis, _indices) = gen.build_light().unwrap();
+        assert_eq!(basis, vec![(join_species(3, 0, length), length)]);
+    }
+}
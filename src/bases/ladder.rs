@@ -0,0 +1,114 @@
+use crate::prelude::*;
+use crate::states::bit_fns::sum_bit;
+
+#[derive(Clone, Debug)]
+pub struct BasisLadder{
+    pub ladder : Ladder,
+    pub value : Box<EigenNumMomentum>,
+    pub leg_parity : Option<usize>,
+}
+
+impl BasisLadder{
+    pub fn new(v : EigenNumMomentum, ladder : Ladder) -> Self{
+        // momentum k is in units of 2π / rungs
+        Self{
+            ladder,
+            value : Box::new(v),
+            leg_parity : None,
+        }
+    }
+
+    pub fn with_leg_parity(v : EigenNumMomentum, ladder : Ladder, q : usize) -> Self{
+        // eigenvalue (-1)^q of leg exchange
+        Self{
+            ladder,
+            value : Box::new(v),
+            leg_parity : Some(q % 2),
+        }
+    }
+
+    pub fn length(&self) -> usize{
+        self.ladder.num_sites()
+    }
+
+    pub fn value(&self) -> EigenNumMomentum{
+        *self.value
+    }
+
+    pub fn character_is_trivial(&self, d : usize, s : usize) -> bool{
+        // e^{-2πikd/L} (-1)^{qs} == 1
+        let l = self.ladder.rungs;
+        let q = self.leg_parity.unwrap_or(0);
+        (2 * self.value.wave_number() * d + q * s * l) % (2 * l) == 0
+    }
+
+    pub fn stabilizer(&self, rep : usize) -> usize{
+        self.ladder.orbit(rep, self.leg_parity.is_some()).iter().filter(|x| x.0 == rep).count()
+    }
+
+    pub fn group_order(&self) -> usize{
+        if self.leg_parity.is_some() {2 * self.ladder.rungs} else {self.ladder.rungs}
+    }
+
+    pub fn build_light(&self) -> Result<(Vec<(usize, usize)>, FnvHashMap<usize, (usize, usize, usize)>), Error>{
+        // indices map a configuration c = T^d P^s rep to (index of rep, d, s)
+        let length = self.length();
+        let exchange = self.leg_parity.is_some();
+        let mut basis : Vec<(usize, usize)> = Vec::new();
+        let mut indices : FnvHashMap<usize, (usize, usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..(1 << length){
+            if sum_bit(n) != self.value.total_number(){
+                continue;
+            }
+
+            let orbit = self.ladder.orbit(n, exchange);
+            if orbit.iter().any(|x| x.0 < n)
+                || !orbit.iter().filter(|x| x.0 == n).all(|x| self.character_is_trivial(x.1, x.2)){
+                continue;
+            }
+
+            for (c, d, s) in orbit{
+                indices.entry(c).or_insert((idx, d, s));
+            }
+            basis.push((n, length));
+            idx += 1;
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_basis_ladder(){
+        let ladder = Ladder::new(2, 3);
+
+        let total : usize = (0..=6).flat_map(|m| (0..3).map(move |k| (m, k)))
+                            .filter_map(|(m, k)| BasisLadder::new(EigenNumMomentum::new(m, k), ladder).build_light().ok())
+                            .map(|(basis, _)| basis.len())
+                            .sum();
+        assert_eq!(total, 64);
+
+        let total : usize = (0..=6).flat_map(|m| (0..3).flat_map(move |k| (0..2).map(move |q| (m, k, q))))
+                            .filter_map(|(m, k, q)| BasisLadder::with_leg_parity(EigenNumMomentum::new(m, k), ladder, q).build_light().ok())
+                            .map(|(basis, _)| basis.len())
+                            .sum();
+        assert_eq!(total, 64);
+
+        // single particle : k = 0 with even and odd leg parity
+        let gen = BasisLadder::with_leg_parity(EigenNumMomentum::new(1, 0), ladder, 1);
+        let (basis, indices) = gen.build_light().unwrap();
+        assert_eq!(basis, vec![(1, 6)]);
+        assert_eq!(indices.get(&(1 << 3)), Some(&(0, 0, 1)));
+        assert_eq!(gen.stabilizer(1), 1);
+    }
+}
@@ -2,6 +2,10 @@ use crate::{prelude::*, states::bit_fns::{is_rep, sum_bit}};
 
 pub mod number;
 pub mod momentum;
+pub mod parity;
+pub mod hubbard;
+pub mod ladder;
+pub mod embedding;
 
 #[derive(Clone, Debug)]
 pub struct BasisGenerator<I : EigenValue>{
@@ -0,0 +1,123 @@
+use crate::{prelude::*, states::bit_fns::{sum_bit, is_rep}};
+
+pub type BasisPK = BasisGenerator<EigenParityMomentum>;
+
+impl BasisPK{
+    pub fn new(v : EigenParityMomentum, length : usize) -> Self{
+        Self{
+            length,
+            value : Box::new(v),
+        }
+    }
+
+    pub fn length(&self) -> usize{
+        self.length
+    }
+
+    pub fn value(&self) -> EigenParityMomentum{
+        *self.value
+    }
+
+    pub fn check_commensurability(&self, period : usize) -> bool{
+        self.value.check_commensurability(period, self.length)
+    }
+
+    pub fn build(&self) -> Result<(Vec<ParityMomentumState>, FnvHashMap<RepParityMomentum, (usize, usize)>), Error>{
+        let max_state = 1 << self.length;
+        let eigen_v = &self.value;
+        let mut basis : Vec<ParityMomentumState> = Vec::new();
+        let mut indices : FnvHashMap<RepParityMomentum, (usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..max_state{
+            let state = SimpleState::new(n, self.length);
+
+            if let Some(pkstate) = ParityMomentumState::new(&state, &self.value){
+                for (num, (i, _coeff)) in pkstate.state.iter(){
+                    indices.insert(Representation(**eigen_v, *num), (idx, *i));
+                }
+
+                basis.push(pkstate);
+                idx += 1;
+            }
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+
+    pub fn build_light(&self) -> Result<(Vec<(usize, usize)>, FnvHashMap<usize, (usize, usize)>), Error>{
+        let length = self.length;
+        let max_state = 1 << length;
+        let mut basis : Vec<(usize, usize)> = Vec::new();
+        let mut indices : FnvHashMap<usize, (usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..max_state{
+            if sum_bit(n) % 2 != self.value.parity()
+                || !self.check_commensurability(period_unsafe(n, length))
+                || !is_rep(n, length){
+                continue;
+            }
+
+            let mut temp = n;
+            let mut i = 0;
+            loop{
+                indices.insert(temp, (idx, i));
+                temp = cyclic_move_unsafe(temp, length);
+                i += 1;
+                if temp == n{
+                    break;
+                }
+            }
+
+            basis.push((n, length));
+            idx += 1;
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_basis_light_pk(){
+        let length = 4;
+
+        let gen = BasisPK::new(EigenParityMomentum::new(0, 0), length);
+        let (base, indices) = gen.build_light().unwrap();
+        assert_eq!(base, vec![(0, 4), (3, 4), (5, 4), (15, 4)]);
+        assert_eq!(indices.get(&10), Some(&(2, 1)));
+        assert_eq!(indices.get(&12), Some(&(1, 2)));
+        assert_eq!(indices.get(&1), None);
+
+        let gen = BasisPK::new(EigenParityMomentum::new(1, 2), length);
+        let (base, _indices) = gen.build_light().unwrap();
+        assert_eq!(base, vec![(1, 4), (7, 4)]);
+
+        let total : usize = (0..2).flat_map(|p| (0..length).map(move |k| (p, k)))
+                            .map(|(p, k)| BasisPK::new(EigenParityMomentum::new(p, k), length).build_light().unwrap().0.len())
+                            .sum();
+        assert_eq!(total, 1 << length);
+    }
+
+    #[test]
+    fn test_basis_pk(){
+        let length = 4;
+        let egn_v = EigenParityMomentum::new(1, 1);
+        let (base, indices) = BasisPK::new(egn_v, length).build().unwrap();
+        assert_eq!(base,
+            vec![ParityMomentumState::new(&(1, length), &egn_v).unwrap(),
+                 ParityMomentumState::new(&(7, length), &egn_v).unwrap()]);
+        assert_eq!(indices.get(&Representation(egn_v, 2)), Some(&(0, 3)));
+    }
+}
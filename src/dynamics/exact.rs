@@ -0,0 +1,84 @@
+use crate::prelude::*;
+use super::check_dim;
+
+#[derive(Clone, Debug)]
+pub struct ExactEvolution{
+    pub eval : Array1<f64>,
+    // columns are eigenvectors, real when the Hamiltonian is stored real
+    pub evec : SectorMatrix,
+}
+
+impl ExactEvolution{
+    pub fn new(hamiltonian : &SectorMatrix) -> Result<Self, Error>{
+        let (eval, evec) = hamiltonian.eigh()?;
+        Ok(Self{
+            eval,
+            evec,
+        })
+    }
+
+    pub fn from_eigh(eval : Array1<f64>, evec : SectorMatrix) -> Self{
+        // reuse an eigendecomposition computed elsewhere
+        Self{
+            eval,
+            evec,
+        }
+    }
+
+    pub fn dim(&self) -> usize{
+        self.eval.len()
+    }
+
+    pub fn to_eigenbasis(&self, psi : &Array1<Complex64>) -> Array1<Complex64>{
+        self.evec.adjoint_dot(psi)
+    }
+
+    pub fn propagator(&self, t : f64) -> Array2<Complex64>{
+        // exp(-iHt) in the sector basis
+        let phase = self.eval.map(|&e| Complex64::new(0f64, -e * t).exp());
+        self.evec.diag_sandwich(&phase)
+    }
+
+    pub fn evolve(&self, psi : &Array1<Complex64>, t : f64) -> Result<Array1<Complex64>, Error>{
+        check_dim(self.dim(), psi)?;
+        let coeff = self.to_eigenbasis(psi);
+        let phase = self.eval.map(|&e| Complex64::new(0f64, -e * t).exp());
+        Ok(self.evec.matvec(&(coeff * phase)))
+    }
+
+    pub fn evolve_at(&self, psi : &Array1<Complex64>, times : &[f64]) -> Result<Vec<Array1<Complex64>>, Error>{
+        check_dim(self.dim(), psi)?;
+        let coeff = self.to_eigenbasis(psi);
+        Ok(times.iter().map(|&t| {
+            let phase = self.eval.map(|&e| Complex64::new(0f64, -e * t).exp());
+            self.evec.matvec(&(&coeff * &phase))
+        }).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use super::*;
+
+    #[test]
+    fn test_exact_evolution(){
+        let h = SectorMatrix::Real(arr2(&[[0.0, 1.0], [1.0, 0.0]]));
+        let evolution = ExactEvolution::new(&h).unwrap();
+        let psi = arr1(&[Complex64::from(1.0), Complex64::from(0.0)]);
+
+        let times = [0.0, 0.3, 1.7];
+        for (&t, phi) in times.iter().zip(evolution.evolve_at(&psi, &times).unwrap().iter()){
+            close_l2(phi, &arr1(&[Complex64::from(t.cos()), Complex64::new(0.0, -t.sin())]), 1e-10);
+            close_l2(phi, &evolution.propagator(t).dot(&psi), 1e-10);
+        }
+        assert!(evolution.evolve(&arr1(&[Complex64::from(1.0)]), 1.0).is_err());
+
+        // complex storage gives the same propagator
+        let complex = ExactEvolution::new(&SectorMatrix::Complex(h.to_complex())).unwrap();
+        assert!(evolution.evec.is_real() && !complex.evec.is_real());
+        close_l2(&complex.propagator(0.9), &evolution.propagator(0.9), 1e-10);
+        close_l2(&complex.evolve(&psi, 0.9).unwrap(), &evolution.evolve(&psi, 0.9).unwrap(), 1e-10);
+    }
+}
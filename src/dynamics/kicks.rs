@@ -0,0 +1,185 @@
+use ndarray_linalg::EigVals;
+use rand::{Rng, distributions::Distribution};
+use crate::prelude::*;
+
+fn energy_change_from(unitary : &Array2<Complex64>, eval0 : &Array1<f64>) -> Array1<f64>{
+    // sum_m |<m|U|n>|^2 E_m - E_n
+    let n = eval0.len();
+    (0..n).map(|j| (0..n).map(|i| unitary[[i, j]].norm_sqr() * eval0[i]).sum::<f64>() - eval0[j]).collect()
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct KickSequence{
+    pub strengths : Vec<f64>,
+    // waiting time under H0 after each kick
+    pub waits : Vec<f64>,
+}
+
+impl KickSequence{
+    pub fn new(strengths : Vec<f64>, waits : Vec<f64>) -> Result<Self, Error>{
+        if strengths.len() != waits.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Ok(Self{
+            strengths,
+            waits,
+        })
+    }
+
+    pub fn periodic(r : f64, wait : f64, n : usize) -> Self{
+        Self{
+            strengths : vec![r; n],
+            waits : vec![wait; n],
+        }
+    }
+
+    pub fn sample_independent<D, R>(n : usize, wait : f64, dist : &D, rng : &mut R) -> Self
+        where D : Distribution<f64>,
+              R : Rng{
+        Self{
+            strengths : (0..n).map(|_| dist.sample(rng)).collect(),
+            waits : vec![wait; n],
+        }
+    }
+
+    pub fn sample_correlated<D, R>(n : usize, wait : f64, rho : f64, dist : &D, rng : &mut R) -> Self
+        where D : Distribution<f64>,
+              R : Rng{
+        // r_{i+1} = rho * r_i + sqrt(1 - rho^2) * xi_{i+1}, which keeps the variance of xi
+        let mut strengths : Vec<f64> = Vec::with_capacity(n);
+        let scale = (1f64 - rho * rho).max(0f64).sqrt();
+        for i in 0..n{
+            let xi = dist.sample(rng);
+            let r = if i == 0 {xi} else {rho * strengths[i - 1] + scale * xi};
+            strengths.push(r);
+        }
+        Self{
+            strengths,
+            waits : vec![wait; n],
+        }
+    }
+
+    pub fn len(&self) -> usize{
+        self.strengths.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.strengths.is_empty()
+    }
+
+    pub fn unitary_eigenbasis(&self, quench : &Quench) -> Result<Array2<Complex64>, Error>{
+        // product of kicks and waits in the eigenbasis of h0, first kick acts first
+        let mut total : Array2<Complex64> = Array2::eye(quench.dim());
+        for (&r, &wait) in self.strengths.iter().zip(self.waits.iter()){
+            let kick = quench.unitary_eigenbasis(r)?;
+            let phase = quench.free_evolution(wait);
+            let mut step = kick.dot(&total);
+            for (mut row, &p) in step.outer_iter_mut().zip(phase.iter()){
+                row *= p;
+            }
+            total = step;
+        }
+        Ok(total)
+    }
+
+    pub fn energy_change(&self, quench : &Quench) -> Result<Array1<f64>, Error>{
+        // accumulated energy change for each initial eigenstate of h0
+        Ok(energy_change_from(&self.unitary_eigenbasis(quench)?, &quench.eval0))
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Floquet{
+    // one period operator in the eigenbasis of h0
+    pub operator : Array2<Complex64>,
+    pub period : f64,
+    eval0 : Array1<f64>,
+}
+
+impl Floquet{
+    pub fn new(quench : &Quench, r : f64, wait : f64) -> Result<Self, Error>{
+        let operator = KickSequence::periodic(r, wait, 1).unitary_eigenbasis(quench)?;
+        Ok(Self{
+            operator,
+            period : quench.duration() + wait,
+            eval0 : quench.eval0.clone(),
+        })
+    }
+
+    pub fn power(&self, n : usize) -> Array2<Complex64>{
+        // F^n by repeated squaring
+        let mut result : Array2<Complex64> = Array2::eye(self.operator.nrows());
+        let mut base = self.operator.clone();
+        let mut n = n;
+        while n > 0{
+            if n % 2 == 1{
+                result = base.dot(&result);
+            }
+            base = base.dot(&base);
+            n /= 2;
+        }
+        result
+    }
+
+    pub fn energy_change(&self, n : usize) -> Array1<f64>{
+        energy_change_from(&self.power(n), &self.eval0)
+    }
+
+    pub fn quasienergies(&self) -> Result<Array1<f64>, Error>{
+        // eigenvalues e^{-i eps T} of F, eps in (-π/T, π/T] and sorted
+        if self.period == 0f64{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let evals = self.operator.eigvals().map_err(|c| Error::make_error_msg(format_args!("{}", c).to_string()))?;
+        let mut eps : Vec<f64> = evals.iter().map(|x| -x.arg() / self.period).collect();
+        eps.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Ok(Array1::from(eps))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use rand::distributions::Uniform;
+    use crate::dynamics::xxz_ising;
+    use super::*;
+
+    fn quench(form : QuenchForm) -> Quench{
+        let (h0, h1) = xxz_ising(6);
+        Quench::new(h0, h1, form).unwrap()
+    }
+
+    #[test]
+    fn test_kick_sequence(){
+        let quench = quench(QuenchForm::Evolve(1f64));
+
+        // single kick is the quench itself
+        let single = KickSequence::new(vec![0.4], vec![2.0]).unwrap();
+        close_l2(&single.energy_change(&quench).unwrap(), &quench.energy_change(0.4).unwrap(), 1e-10);
+
+        let mut rng = rng_seed(1234);
+        let seq = KickSequence::sample_independent(5, 0.5, &Uniform::new(-1f64, 1f64), &mut rng);
+        assert_eq!(seq.len(), 5);
+        assert!(seq.energy_change(&quench).unwrap().sum().abs() < 1e-10);
+
+        let seq = KickSequence::sample_correlated(5, 0.5, 1.0, &Uniform::new(-1f64, 1f64), &mut rng);
+        assert!(seq.strengths.iter().all(|&r| (r - seq.strengths[0]).abs() < 1e-12));
+
+        assert!(KickSequence::new(vec![0.1], vec![]).is_err());
+    }
+
+    #[test]
+    fn test_floquet(){
+        let quench = quench(QuenchForm::Evolve(0.3));
+        let floquet = Floquet::new(&quench, 0.4, 0.2).unwrap();
+        let seq = KickSequence::periodic(0.4, 0.2, 7);
+        close_l2(&floquet.power(7), &seq.unitary_eigenbasis(&quench).unwrap(), 1e-10);
+        close_l2(&floquet.energy_change(7), &seq.energy_change(&quench).unwrap(), 1e-10);
+
+        // without kick, quasienergies are the energies of h0 when |E| T < π
+        let floquet = Floquet::new(&quench, 0.0, 0.1).unwrap();
+        assert!(quench.eval0.iter().all(|e| e.abs() * floquet.period < PI));
+        close_l2(&floquet.quasienergies().unwrap(), &quench.eval0, 1e-8);
+    }
+}
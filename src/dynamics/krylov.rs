@@ -0,0 +1,209 @@
+use crate::prelude::*;
+use crate::error::MAX_TRIAL;
+use crate::hamiltonian::sparse::MatVec;
+use super::{check_dim, norm};
+
+pub(crate) fn lanczos<M>(op : &M, start : &Array1<Complex64>, m : usize) -> (Vec<Array1<Complex64>>, Vec<f64>, Vec<f64>)
+    where M : MatVec + ?Sized{
+    // Lanczos basis of a normalized start vector with full reorthogonalization, stops early on an invariant subspace.
+    // beta[j] couples basis[j] and basis[j + 1], the last one is the residual norm.
+    let mut basis : Vec<Array1<Complex64>> = vec![start.clone()];
+    let mut alpha : Vec<f64> = Vec::with_capacity(m);
+    let mut beta : Vec<f64> = Vec::with_capacity(m);
+
+    for j in 0..m{
+        let mut w = op.matvec(&basis[j]);
+        alpha.push(basis[j].iter().zip(w.iter()).map(|(v, x)| v.conj() * x).sum::<Complex64>().re);
+        for v in basis.iter(){
+            let overlap : Complex64 = v.iter().zip(w.iter()).map(|(v, x)| v.conj() * x).sum();
+            w = w - v * overlap;
+        }
+        let b = norm(&w);
+        beta.push(b);
+        if b < 1e-12 || j + 1 == m{
+            break;
+        }
+        basis.push(w / Complex64::from(b));
+    }
+    return (basis, alpha, beta);
+}
+
+pub(crate) fn tridiagonal_eigh(alpha : &[f64], beta : &[f64]) -> Result<(Array1<f64>, Array2<f64>), Error>{
+    let s = alpha.len();
+    let mut tri : Array2<f64> = Array2::zeros((s, s));
+    for j in 0..s{
+        tri[[j, j]] = alpha[j];
+        if j + 1 < s{
+            tri[[j, j + 1]] = beta[j];
+            tri[[j + 1, j]] = beta[j];
+        }
+    }
+    tri.eigh(UPLO::Lower).map_err(|c| Error::make_error_msg(format_args!("{}", c).to_string()))
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct KrylovEvolution{
+    pub max_dim : usize,
+    // bound of the a posteriori error estimate for each time step
+    pub tol : f64,
+}
+
+impl KrylovEvolution{
+    pub fn new(max_dim : usize, tol : f64) -> Self{
+        Self{
+            max_dim,
+            tol,
+        }
+    }
+
+    pub fn step<M>(&self, op : &M, psi : &Array1<Complex64>, dt : f64) -> Result<(Array1<Complex64>, f64), Error>
+        where M : MatVec + ?Sized{
+        // exp(-iH dt)|psi> in the Lanczos basis of |psi>, with error estimate beta_m * |(exp(-iT dt) e_1)_m|
+        let (result, error, _) = self.lanczos_step(op, psi, dt)?;
+        return Ok((result, error));
+    }
+
+    fn lanczos_step<M>(&self, op : &M, psi : &Array1<Complex64>, dt : f64) -> Result<(Array1<Complex64>, f64, usize), Error>
+        where M : MatVec + ?Sized{
+        // same as step, also returning the number of Lanczos vectors
+        check_dim(op.dim(), psi)?;
+        let psi_norm = norm(psi);
+        if psi_norm == 0f64{
+            return Ok((psi.clone(), 0f64, 0));
+        }
+
+        let m = self.max_dim.min(op.dim()).max(1);
+        let (basis, alpha, beta) = lanczos(op, &(psi / Complex64::from(psi_norm)), m);
+        let s = alpha.len();
+        let (theta, vecs) = tridiagonal_eigh(&alpha, &beta)?;
+        let coeff : Array1<Complex64> = (0..s).map(|i| {
+            (0..s).map(|l| Complex64::new(0f64, -theta[l] * dt).exp() * vecs[[i, l]] * vecs[[0, l]]).sum()
+        }).collect();
+
+        let residual = beta[s - 1];
+        let error = if residual < 1e-12 {0f64} else {residual * coeff[s - 1].norm()};
+
+        let mut result : Array1<Complex64> = Array1::zeros(op.dim());
+        for (c, v) in coeff.iter().zip(basis.iter()){
+            result = result + v * (*c * psi_norm);
+        }
+        return Ok((result, error, s));
+    }
+
+    pub fn evolve<M>(&self, op : &M, psi : &Array1<Complex64>, t : f64) -> Result<Array1<Complex64>, Error>
+        where M : MatVec + ?Sized{
+        self.adaptive_evolve(op, psi, t).map(|x| x.0)
+    }
+
+    fn adaptive_evolve<M>(&self, op : &M, psi : &Array1<Complex64>, t : f64) -> Result<(Array1<Complex64>, Vec<f64>, usize), Error>
+        where M : MatVec + ?Sized{
+        // halve the time step until the error estimate is below tol, and grow it again after accepted steps.
+        // Returns the state, accepted time steps and the number of rejected steps.
+        let mut state = psi.clone();
+        let mut remaining = t;
+        let mut dt = t;
+        let mut trial = 0;
+        let mut accepted : Vec<f64> = Vec::new();
+        let mut rejected = 0;
+
+        while remaining.abs() > 0f64{
+            if dt.abs() > remaining.abs(){
+                dt = remaining;
+            }
+            let (next, error, s) = self.lanczos_step(op, &state, dt)?;
+            if error <= self.tol{
+                state = next;
+                remaining -= dt;
+                accepted.push(dt);
+                trial = 0;
+                // error scales roughly as dt^s, grow up to twice with a safety factor 1/2
+                let growth = if error == 0f64 {2f64} else {(0.5 * self.tol / error).powf(1f64 / s as f64).min(2f64)};
+                if growth > 1f64{
+                    dt *= growth;
+                }
+            } else {
+                dt /= 2f64;
+                rejected += 1;
+                trial += 1;
+                if trial > MAX_TRIAL{
+                    return Err(Error::make_error_msg(format!("Krylov evolution does not reach tolerance {:e}", self.tol)));
+                }
+            }
+        }
+
+        return Ok((state, accepted, rejected));
+    }
+
+    pub fn evolve_at<M>(&self, op : &M, psi : &Array1<Complex64>, times : &[f64]) -> Result<Vec<Array1<Complex64>>, Error>
+        where M : MatVec + ?Sized{
+        // states at each time, evolving from the previous output
+        let mut result : Vec<Array1<Complex64>> = Vec::with_capacity(times.len());
+        let mut state = psi.clone();
+        let mut now = 0f64;
+
+        for &t in times.iter(){
+            state = self.evolve(op, &state, t - now)?;
+            now = t;
+            result.push(state.clone());
+        }
+        return Ok(result);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use crate::hamiltonian::sparse::sparse_sector_hamiltonian;
+    use super::*;
+
+    #[test]
+    fn test_krylov_evolution(){
+        let length = 12;
+        let egn_v = EigenNumMomentum::new(6, 0);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let xxz = PeriodicNearestXXZ::new(1f64, 0.5f64);
+
+        let dense = SectorMatrix::Complex(sector_hamiltonian(&basis, &indices, egn_v, &xxz));
+        let sparse = sparse_sector_hamiltonian(&basis, &indices, egn_v, &xxz);
+        let exact = ExactEvolution::new(&dense).unwrap();
+
+        let mut psi : Array1<Complex64> = Array1::zeros(basis.len());
+        psi[0] = Complex64::from(1f64);
+        let times = [0.0, 0.5, 1.0, 2.5];
+
+        let krylov = KrylovEvolution::new(20, 1e-10);
+        let result = krylov.evolve_at(&sparse, &psi, &times).unwrap();
+        let truth = exact.evolve_at(&psi, &times).unwrap();
+        for (x, y) in result.iter().zip(truth.iter()){
+            close_l2(x, y, 1e-7);
+            assert!((norm(x) - 1f64).abs() < 1e-10);
+        }
+
+        // backward evolution returns to the initial state
+        let back = krylov.evolve(&sparse, &result[3], -2.5).unwrap();
+        close_l2(&back, &psi, 1e-7);
+    }
+
+    #[test]
+    fn test_krylov_step_growth(){
+        let length = 12;
+        let egn_v = EigenNumMomentum::new(6, 0);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let sparse = sparse_sector_hamiltonian(&basis, &indices, egn_v, &PeriodicNearestXXZ::new(1f64, 0.5f64));
+        let exact = ExactEvolution::new(&SectorMatrix::Complex(sparse.to_dense())).unwrap();
+
+        let mut psi : Array1<Complex64> = Array1::zeros(basis.len());
+        psi[0] = Complex64::from(1f64);
+
+        // the first trial step over the whole time is far beyond the Krylov dimension
+        let t = 8f64;
+        let krylov = KrylovEvolution::new(10, 1e-9);
+        let (state, accepted, rejected) = krylov.adaptive_evolve(&sparse, &psi, t).unwrap();
+        assert!(rejected > 0);
+        assert!((accepted.iter().sum::<f64>() - t).abs() < 1e-10);
+        // time step is not stuck at the size after the first rejections
+        assert!(accepted.windows(2).any(|w| w[1] > w[0]));
+        close_l2(&state, &exact.evolve(&psi, t).unwrap(), 1e-6);
+    }
+}
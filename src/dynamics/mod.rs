@@ -0,0 +1,29 @@
+use crate::prelude::*;
+
+pub mod exact;
+pub mod krylov;
+pub mod quench;
+pub mod kicks;
+pub mod ramp;
+pub mod work;
+pub mod passivity;
+
+pub(crate) fn check_dim(dim : usize, psi : &Array1<Complex64>) -> Result<(), Error>{
+    if psi.len() != dim{
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+    Ok(())
+}
+
+pub(crate) fn norm(psi : &Array1<Complex64>) -> f64{
+    psi.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt()
+}
+
+#[cfg(test)]
+pub(crate) fn xxz_ising(length : usize) -> (SectorMatrix, SectorMatrix){
+    // XXZ(1, 2) and Ising(1) at half filling and k = 0, shared by the tests of this module
+    let egn_v = EigenNumMomentum::new(length / 2, 0);
+    let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+    (SectorMatrix::build(&basis, &indices, egn_v, &PeriodicNearestXXZ::new(1f64, 2f64)),
+     SectorMatrix::build(&basis, &indices, egn_v, &PeriodicIsing::new(1f64)))
+}
@@ -0,0 +1,207 @@
+use crate::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiagonalState{
+    // state diagonal in the eigenbasis of H0
+    pub energies : Array1<f64>,
+    pub populations : Array1<f64>,
+}
+
+impl DiagonalState{
+    pub fn new(energies : Array1<f64>, populations : Array1<f64>) -> Result<Self, Error>{
+        if energies.len() != populations.len() || populations.iter().any(|&p| p < 0f64){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        if (populations.sum() - 1f64).abs() > 1e-10{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Ok(Self{
+            energies,
+            populations,
+        })
+    }
+
+    pub fn eigenstate(energies : &Array1<f64>, n : usize) -> Result<Self, Error>{
+        let mut populations : Array1<f64> = Array1::zeros(energies.len());
+        if n >= energies.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        populations[n] = 1f64;
+        Self::new(energies.clone(), populations)
+    }
+
+    pub fn thermal(energies : &Array1<f64>, beta : f64) -> Self{
+        Self{
+            energies : energies.clone(),
+            populations : thermal_populations(energies, beta),
+        }
+    }
+
+    pub fn energy(&self) -> f64{
+        self.energies.dot(&self.populations)
+    }
+
+    pub fn is_passive(&self, tol : f64) -> bool{
+        // populations do not increase with energy
+        let n = self.energies.len();
+        (0..n).all(|i| (0..n).all(|j| self.energies[i] >= self.energies[j] || self.populations[i] + tol >= self.populations[j]))
+    }
+
+    pub fn passive_state(&self) -> Self{
+        // same spectrum of populations rearranged in decreasing order over increasing energies
+        let mut order : Vec<usize> = (0..self.energies.len()).collect();
+        order.sort_by(|&a, &b| self.energies[a].partial_cmp(&self.energies[b]).unwrap());
+        let mut sorted : Vec<f64> = self.populations.to_vec();
+        sorted.sort_by(|a, b| b.partial_cmp(a).unwrap());
+
+        let mut populations : Array1<f64> = Array1::zeros(self.energies.len());
+        for (&idx, &p) in order.iter().zip(sorted.iter()){
+            populations[idx] = p;
+        }
+        Self{
+            energies : self.energies.clone(),
+            populations,
+        }
+    }
+
+    pub fn ergotropy(&self) -> f64{
+        // maximal work extractable by a unitary
+        self.energy() - self.passive_state().energy()
+    }
+
+    pub fn copies(&self, k : usize) -> Self{
+        // k independent copies with H = sum of H0 on each copy
+        let mut energies : Vec<f64> = vec![0f64];
+        let mut populations : Vec<f64> = vec![1f64];
+        for _ in 0..k{
+            let mut next_e : Vec<f64> = Vec::with_capacity(energies.len() * self.energies.len());
+            let mut next_p : Vec<f64> = Vec::with_capacity(energies.len() * self.energies.len());
+            for (&e, &p) in energies.iter().zip(populations.iter()){
+                for (&e1, &p1) in self.energies.iter().zip(self.populations.iter()){
+                    next_e.push(e + e1);
+                    next_p.push(p * p1);
+                }
+            }
+            energies = next_e;
+            populations = next_p;
+        }
+        Self{
+            energies : Array1::from(energies),
+            populations : Array1::from(populations),
+        }
+    }
+
+    pub fn is_k_passive(&self, k : usize, tol : f64) -> bool{
+        self.copies(k).is_passive(tol)
+    }
+
+    pub fn is_completely_passive(&self, tol : f64) -> bool{
+        // passive for every k if and only if the state is a Gibbs state with beta >= 0 (including ground states).
+        // beta is fitted on the populated levels, then every level is compared with the Gibbs state.
+        let support : Vec<usize> = (0..self.energies.len()).filter(|&i| self.populations[i] > tol).collect();
+        if support.is_empty(){
+            return false;
+        }
+
+        let n = support.len() as f64;
+        let mean_e = support.iter().map(|&i| self.energies[i]).sum::<f64>() / n;
+        let var_e : f64 = support.iter().map(|&i| (self.energies[i] - mean_e).powi(2)).sum();
+
+        let expected = if var_e > 0f64{
+            // ln p = a - beta E by least squares over the support
+            let mean_l = support.iter().map(|&i| self.populations[i].ln()).sum::<f64>() / n;
+            let cov : f64 = support.iter().map(|&i| (self.energies[i] - mean_e) * (self.populations[i].ln() - mean_l)).sum();
+            let beta = -cov / var_e;
+            if beta < -tol{
+                return false;
+            }
+            DiagonalState::thermal(&self.energies, beta).populations
+        } else {
+            // beta = infinity : uniform over the lowest level only
+            let ground = self.energies.iter().cloned().fold(f64::INFINITY, f64::min);
+            let lowest = self.energies.map(|&e| if (e - ground).abs() <= tol {1f64} else {0f64});
+            let count = lowest.sum();
+            lowest / count
+        };
+        expected.iter().zip(self.populations.iter()).all(|(x, y)| (x - y).abs() <= tol)
+    }
+
+    pub fn kick_work(&self, quench : &Quench, r : f64) -> Result<f64, Error>{
+        // average work of a single kick of strength r, the process of quench_parallel
+        if quench.dim() != self.energies.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Ok(quench.energy_change(r)?.dot(&self.populations))
+    }
+
+    pub fn minimal_kick_work(&self, quench : &Quench, strengths : &[f64]) -> Result<(f64, f64), Error>{
+        // (r, work) minimizing the average work over the given strengths
+        let mut best : Option<(f64, f64)> = None;
+        for &r in strengths.iter(){
+            let w = self.kick_work(quench, r)?;
+            if best.map_or(true, |(_, b)| w < b){
+                best = Some((r, w));
+            }
+        }
+        best.ok_or_else(|| Error::make_error_syntax(ErrorCode::InvalidArgument))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::dynamics::xxz_ising;
+    use super::*;
+
+    #[test]
+    fn test_passivity(){
+        let energies = arr1(&[0.0, 1.0, 2.0]);
+        let state = DiagonalState::new(energies.clone(), arr1(&[0.2, 0.5, 0.3])).unwrap();
+        assert!(!state.is_passive(1e-12));
+        assert!(state.passive_state().is_passive(1e-12));
+        assert!((state.ergotropy() - 0.4).abs() < 1e-12);
+
+        // passive but not completely passive : two copies become active
+        let state = DiagonalState::new(arr1(&[0.0, 1.0, 1.5]), arr1(&[0.5, 0.5, 0.0])).unwrap();
+        assert!(state.is_passive(1e-12));
+        assert!(!state.is_k_passive(2, 1e-12));
+        assert!(!state.is_completely_passive(1e-12));
+
+        let thermal = DiagonalState::thermal(&energies, 0.7);
+        assert!(thermal.is_completely_passive(1e-10));
+        assert!(thermal.is_k_passive(3, 1e-12));
+        assert!(DiagonalState::eigenstate(&energies, 0).unwrap().is_completely_passive(1e-12));
+        assert!(!DiagonalState::eigenstate(&energies, 1).unwrap().is_passive(1e-12));
+        assert!(DiagonalState::new(energies.clone(), arr1(&[0.5, 0.5, 0.5])).is_err());
+
+        // low temperature Gibbs state, excited populations below tol
+        let cold = DiagonalState::thermal(&energies, 20.0);
+        assert!(cold.is_completely_passive(1e-8));
+        assert!(cold.is_completely_passive(1e-12));
+        let degenerate = DiagonalState::new(arr1(&[0.0, 0.0, 1.0]), arr1(&[0.5, 0.5, 0.0])).unwrap();
+        assert!(degenerate.is_completely_passive(1e-12));
+        let half = DiagonalState::new(arr1(&[0.0, 0.0, 1.0]), arr1(&[1.0, 0.0, 0.0])).unwrap();
+        assert!(!half.is_completely_passive(1e-12));
+
+        let empty = DiagonalState{ energies, populations : Array1::zeros(3) };
+        assert!(!empty.is_completely_passive(1e-12));
+    }
+
+    #[test]
+    fn test_minimal_kick_work(){
+        let (h0, h1) = xxz_ising(6);
+        let quench = Quench::new(h0, h1, QuenchForm::Evolve(1f64)).unwrap();
+        let strengths : Vec<f64> = (0..=40).map(|i| -1.0 + 0.05 * (i as f64)).collect();
+
+        // passive states never give work
+        let thermal = DiagonalState::thermal(&quench.eval0, 1.0);
+        let (_, w) = thermal.minimal_kick_work(&quench, &strengths).unwrap();
+        assert!(w > -1e-10);
+
+        // active state is bounded by ergotropy
+        let top = DiagonalState::eigenstate(&quench.eval0, quench.dim() - 1).unwrap();
+        let (_, w) = top.minimal_kick_work(&quench, &strengths).unwrap();
+        assert!(w >= -top.ergotropy() - 1e-10);
+        assert!(w < 0f64);
+    }
+}
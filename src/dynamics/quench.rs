@@ -0,0 +1,120 @@
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum QuenchForm{
+    // U = exp(-i(H0 + r H1) tau)
+    Evolve(f64),
+    // U = exp(-i r H1)
+    Kick,
+}
+
+#[derive(Clone, Debug)]
+pub struct Quench{
+    pub h0 : SectorMatrix,
+    pub h1 : SectorMatrix,
+    pub form : QuenchForm,
+    pub eval0 : Array1<f64>,
+    // eigenvectors of h0 as columns, real when h0 is stored real
+    pub evec0 : SectorMatrix,
+}
+
+impl Quench{
+    pub fn new(h0 : SectorMatrix, h1 : SectorMatrix, form : QuenchForm) -> Result<Self, Error>{
+        let (eval0, evec0) = h0.eigh()?;
+        Self::from_eigh(h0, h1, form, eval0, evec0)
+    }
+
+    pub fn from_eigh(h0 : SectorMatrix, h1 : SectorMatrix, form : QuenchForm, eval0 : Array1<f64>, evec0 : SectorMatrix) -> Result<Self, Error>{
+        // reuse an eigendecomposition of h0 computed elsewhere
+        if h0.dim() != h1.dim() || h0.dim() != eval0.len() || evec0.dim() != eval0.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Ok(Self{
+            h0,
+            h1,
+            form,
+            eval0,
+            evec0,
+        })
+    }
+
+    pub fn dim(&self) -> usize{
+        self.eval0.len()
+    }
+
+    pub fn duration(&self) -> f64{
+        match self.form{
+            QuenchForm::Evolve(tau) => tau,
+            QuenchForm::Kick => 0f64,
+        }
+    }
+
+    pub fn free_evolution(&self, t : f64) -> Array1<Complex64>{
+        // exp(-iH0 t) is diagonal in the eigenbasis of h0
+        self.eval0.map(|&e| Complex64::new(0f64, -e * t).exp())
+    }
+
+    pub fn unitary(&self, r : f64) -> Result<Array2<Complex64>, Error>{
+        // U in the sector basis
+        let (generator, tau) = match self.form{
+            QuenchForm::Evolve(tau) => (self.h0.scaled_add(r, &self.h1)?, tau),
+            QuenchForm::Kick => (self.h1.clone(), r),
+        };
+        Ok(ExactEvolution::new(&generator)?.propagator(tau))
+    }
+
+    pub fn unitary_eigenbasis(&self, r : f64) -> Result<Array2<Complex64>, Error>{
+        // <m|U|n> with eigenstates of h0
+        Ok(self.evec0.sandwich(&self.unitary(r)?))
+    }
+
+    pub fn energy_matrix(&self, r : f64) -> Result<Array2<Complex64>, Error>{
+        // U† H0 U in the eigenbasis of h0
+        let right = self.unitary_eigenbasis(r)?;
+        let left : Array2<Complex64> = conjugate(&right);
+        let ediag = Array2::from_diag(&self.eval0.map(|&x| Complex64::from(x)));
+        Ok(left.dot(&ediag).dot(&right))
+    }
+
+    pub fn energy_change(&self, r : f64) -> Result<Array1<f64>, Error>{
+        // <n|U† H0 U|n> - E_n = sum_m |<m|U|n>|^2 E_m - E_n
+        let right = self.unitary_eigenbasis(r)?;
+        let n = self.dim();
+        Ok((0..n).map(|j| (0..n).map(|i| right[[i, j]].norm_sqr() * self.eval0[i]).sum::<f64>() - self.eval0[j]).collect())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use crate::dynamics::xxz_ising;
+    use super::*;
+
+    #[test]
+    fn test_quench(){
+        let (h0, h1) = xxz_ising(6);
+
+        let quench = Quench::new(h0.clone(), h1.clone(), QuenchForm::Evolve(0.7)).unwrap();
+        // k = 0 keeps the eigenvectors real
+        assert!(quench.evec0.is_real());
+        let evec0 = quench.evec0.to_complex();
+        let conj_evec0 : Array2<Complex64> = conjugate(&evec0);
+        close_l2(&quench.unitary_eigenbasis(0.3).unwrap(), &conj_evec0.dot(&quench.unitary(0.3).unwrap()).dot(&evec0), 1e-10);
+
+        let change = quench.energy_change(0.3).unwrap();
+        let matrix = quench.energy_matrix(0.3).unwrap();
+        close_l2(&matrix.diag().map(|x| x.re), &(&change + &quench.eval0), 1e-10);
+        // total energy change vanishes since trace is invariant
+        assert!(change.sum().abs() < 1e-10);
+
+        // zero strength only changes phases
+        let change = quench.energy_change(0.0).unwrap();
+        close_l2(&change, &Array1::zeros(quench.dim()), 1e-10);
+
+        let kick = Quench::new(h0, h1, QuenchForm::Kick).unwrap();
+        let u = kick.unitary(0.5).unwrap();
+        let conj_u : Array2<Complex64> = conjugate(&u);
+        close_l2(&conj_u.dot(&u), &Array2::eye(quench.dim()), 1e-10);
+    }
+}
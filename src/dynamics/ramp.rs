@@ -0,0 +1,180 @@
+use std::sync::Arc;
+use crate::prelude::*;
+
+#[derive(Clone)]
+pub struct Ramp{
+    // H(t) = H0 + lambda(t) H1 for 0 <= t <= duration
+    pub lambda : Arc<dyn Fn(f64) -> f64 + Send + Sync>,
+    pub duration : f64,
+}
+
+impl Ramp{
+    pub fn linear(r : f64, duration : f64) -> Self{
+        // lambda goes from 0 to r
+        Self::custom(move |t| r * t / duration, duration)
+    }
+
+    pub fn sinusoidal(r : f64, duration : f64) -> Self{
+        // cyclic, lambda(0) = lambda(duration) = 0
+        Self::custom(move |t| r * (PI * t / duration).sin(), duration)
+    }
+
+    pub fn custom<F>(lambda : F, duration : f64) -> Self
+        where F : Fn(f64) -> f64 + Send + Sync + 'static{
+        Self{
+            lambda : Arc::new(lambda),
+            duration,
+        }
+    }
+
+    pub fn value(&self, t : f64) -> f64{
+        (self.lambda)(t)
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RampIntegrator{
+    // number of time steps
+    Magnus2(usize),
+    Magnus4(usize),
+    RungeKutta4(usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct RampWork{
+    pub initial_energies : Array1<f64>,
+    pub final_energies : Array1<f64>,
+    // transition[[m, n]] = |<m(T)|U|n(0)>|^2 between eigenstates of H(T) and H(0)
+    pub transition : Array2<f64>,
+}
+
+impl RampWork{
+    pub fn mean(&self) -> Array1<f64>{
+        // <W>_n = sum_m p(m|n) (E_m(T) - E_n(0))
+        let n = self.initial_energies.len();
+        (0..n).map(|j| (0..n).map(|i| self.transition[[i, j]] * (self.final_energies[i] - self.initial_energies[j])).sum()).collect()
+    }
+
+    pub fn variance(&self) -> Array1<f64>{
+        let n = self.initial_energies.len();
+        let mean = self.mean();
+        (0..n).map(|j| (0..n).map(|i| self.transition[[i, j]] * (self.final_energies[i] - self.initial_energies[j] - mean[j]).powi(2)).sum()).collect()
+    }
+}
+
+fn hermitian_exp(k : &Array2<Complex64>) -> Result<Array2<Complex64>, Error>{
+    // exp(-iK) for hermitian K
+    let evolution = ExactEvolution::new(&SectorMatrix::Complex(k.clone()))?;
+    Ok(evolution.propagator(1f64))
+}
+
+pub fn ramp_unitary(h0 : &SectorMatrix, h1 : &SectorMatrix, ramp : &Ramp, integrator : RampIntegrator) -> Result<Array2<Complex64>, Error>{
+    // time ordered exp(-i int H(t) dt) in the sector basis
+    if h0.dim() != h1.dim(){
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+    let (h0, h1) = (h0.to_complex(), h1.to_complex());
+    let hamiltonian = |t : f64| -> Array2<Complex64> {&h1 * Complex64::from(ramp.value(t)) + &h0};
+    let minus_i = Complex64::new(0f64, -1f64);
+
+    let steps = match integrator{
+        RampIntegrator::Magnus2(n) | RampIntegrator::Magnus4(n) | RampIntegrator::RungeKutta4(n) => n,
+    };
+    if steps == 0{
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+    let dt = ramp.duration / (steps as f64);
+
+    let mut unitary : Array2<Complex64> = Array2::eye(h0.nrows());
+    for s in 0..steps{
+        let t = dt * (s as f64);
+        unitary = match integrator{
+            RampIntegrator::Magnus2(_) => {
+                // midpoint rule
+                hermitian_exp(&(hamiltonian(t + dt / 2f64) * Complex64::from(dt)))?.dot(&unitary)
+            },
+            RampIntegrator::Magnus4(_) => {
+                // two point Gauss-Legendre with commutator correction
+                let c = 3f64.sqrt() / 6f64;
+                let ha = hamiltonian(t + (0.5 - c) * dt);
+                let hb = hamiltonian(t + (0.5 + c) * dt);
+                let commutator = hb.dot(&ha) - ha.dot(&hb);
+                let k = (&ha + &hb) * Complex64::from(dt / 2f64) + commutator * (minus_i * 3f64.sqrt() / 12f64 * dt * dt);
+                hermitian_exp(&k)?.dot(&unitary)
+            },
+            RampIntegrator::RungeKutta4(_) => {
+                // dU/dt = -iH(t)U
+                let f = |time : f64, u : &Array2<Complex64>| -> Array2<Complex64> {hamiltonian(time).dot(u) * minus_i};
+                let k1 = f(t, &unitary);
+                let k2 = f(t + dt / 2f64, &(&unitary + &(&k1 * Complex64::from(dt / 2f64))));
+                let k3 = f(t + dt / 2f64, &(&unitary + &(&k2 * Complex64::from(dt / 2f64))));
+                let k4 = f(t + dt, &(&unitary + &(&k3 * Complex64::from(dt))));
+                &unitary + &((k1 + k2 * Complex64::from(2f64) + k3 * Complex64::from(2f64) + k4) * Complex64::from(dt / 6f64))
+            },
+        };
+    }
+
+    return Ok(unitary);
+}
+
+pub fn ramp_work(h0 : &SectorMatrix, h1 : &SectorMatrix, ramp : &Ramp, integrator : RampIntegrator) -> Result<RampWork, Error>{
+    // two point measurement with eigenstates of H(0) and H(T)
+    let unitary = ramp_unitary(h0, h1, ramp, integrator)?;
+    let (initial_energies, initial_vec) = h0.scaled_add(ramp.value(0f64), h1)?.eigh()?;
+    let (final_energies, final_vec) = h0.scaled_add(ramp.value(ramp.duration), h1)?.eigh()?;
+
+    let conj_final : Array2<Complex64> = conjugate(&final_vec.into_complex());
+    let amplitude = conj_final.dot(&unitary).dot(&initial_vec.into_complex());
+
+    Ok(RampWork{
+        initial_energies,
+        final_energies,
+        transition : amplitude.map(|x| x.norm_sqr()),
+    })
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use crate::dynamics::xxz_ising;
+    use super::*;
+
+    #[test]
+    fn test_constant_ramp(){
+        let (h0, h1) = xxz_ising(6);
+        let ramp = Ramp::custom(|_| 0.3, 1.5);
+        let truth = ExactEvolution::new(&h0.scaled_add(0.3, &h1).unwrap()).unwrap().propagator(1.5);
+
+        for &integrator in &[RampIntegrator::Magnus2(3), RampIntegrator::Magnus4(3), RampIntegrator::RungeKutta4(2000)]{
+            close_l2(&ramp_unitary(&h0, &h1, &ramp, integrator).unwrap(), &truth, 1e-8);
+        }
+        assert!(ramp_unitary(&h0, &h1, &ramp, RampIntegrator::Magnus2(0)).is_err());
+    }
+
+    #[test]
+    fn test_sudden_and_adiabatic(){
+        let (h0, h1) = xxz_ising(4);
+        let r = 0.5;
+
+        // sudden limit : <W>_n = r <n|H1|n>
+        let work = ramp_work(&h0, &h1, &Ramp::linear(r, 1e-6), RampIntegrator::Magnus2(1)).unwrap();
+        let (_, evec0) = h0.eigh().unwrap();
+        let evec0 = evec0.into_complex();
+        let conj_evec0 : Array2<Complex64> = conjugate(&evec0);
+        let truth = conj_evec0.dot(&h1.to_complex()).dot(&evec0).diag().map(|x| r * x.re);
+        close_l2(&work.mean(), &truth, 1e-5);
+
+        // adiabatic limit : <W>_n = E_n(r) - E_n(0) without fluctuation
+        let work = ramp_work(&h0, &h1, &Ramp::linear(r, 100.0), RampIntegrator::Magnus4(400)).unwrap();
+        close_l2(&work.mean(), &(&work.final_energies - &work.initial_energies), 1e-3);
+        assert!(work.variance().iter().all(|&x| x < 1e-3));
+
+        // integrators agree on a cyclic protocol
+        let ramp = Ramp::sinusoidal(r, 2.0);
+        let x = ramp_work(&h0, &h1, &ramp, RampIntegrator::Magnus4(100)).unwrap();
+        let y = ramp_work(&h0, &h1, &ramp, RampIntegrator::RungeKutta4(2000)).unwrap();
+        close_l2(&x.mean(), &y.mean(), 1e-6);
+        assert!(x.mean().sum().abs() < 1e-10);
+    }
+}
@@ -0,0 +1,216 @@
+use crate::prelude::*;
+
+pub fn thermal_populations(energies : &Array1<f64>, beta : f64) -> Array1<f64>{
+    // Gibbs weights exp(-beta E) / Z, shifted by the minimum of beta E to avoid overflow
+    let shift = energies.iter().map(|&e| beta * e).fold(std::f64::INFINITY, f64::min);
+    let weights = energies.map(|&e| (-(beta * e) + shift).exp());
+    let z = weights.sum();
+    weights / z
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct WorkDistribution{
+    // P(W) = sum of weights[i] * delta(W - values[i])
+    pub values : Vec<f64>,
+    pub weights : Vec<f64>,
+}
+
+impl WorkDistribution{
+    pub fn from_transition(initial : &Array1<f64>, last : &Array1<f64>, transition : &Array2<f64>, populations : &Array1<f64>) -> Result<Self, Error>{
+        // transition[[m, n]] = |<m|U|n>|^2, W = last[m] - initial[n]
+        let (n_last, n_init) = transition.dim();
+        if n_init != initial.len() || n_last != last.len() || populations.len() != n_init{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        let mut values : Vec<f64> = Vec::with_capacity(n_last * n_init);
+        let mut weights : Vec<f64> = Vec::with_capacity(n_last * n_init);
+        for n in 0..n_init{
+            if populations[n] == 0f64{
+                continue;
+            }
+            for m in 0..n_last{
+                values.push(last[m] - initial[n]);
+                weights.push(populations[n] * transition[[m, n]]);
+            }
+        }
+        Ok(Self{
+            values,
+            weights,
+        })
+    }
+
+    pub fn from_unitary(eval0 : &Array1<f64>, unitary : &Array2<Complex64>, populations : &Array1<f64>) -> Result<Self, Error>{
+        // cyclic process, unitary in the eigenbasis of H0 as from Quench::unitary_eigenbasis
+        Self::from_transition(eval0, eval0, &unitary.map(|x| x.norm_sqr()), populations)
+    }
+
+    pub fn eigenstate(eval0 : &Array1<f64>, unitary : &Array2<Complex64>, n : usize) -> Result<Self, Error>{
+        if n >= eval0.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let mut populations : Array1<f64> = Array1::zeros(eval0.len());
+        populations[n] = 1f64;
+        Self::from_unitary(eval0, unitary, &populations)
+    }
+
+    pub fn thermal(eval0 : &Array1<f64>, unitary : &Array2<Complex64>, beta : f64) -> Result<Self, Error>{
+        Self::from_unitary(eval0, unitary, &thermal_populations(eval0, beta))
+    }
+
+    pub fn from_ramp(work : &RampWork, populations : &Array1<f64>) -> Result<Self, Error>{
+        Self::from_transition(&work.initial_energies, &work.final_energies, &work.transition, populations)
+    }
+
+    pub fn total_weight(&self) -> f64{
+        self.weights.iter().sum()
+    }
+
+    pub fn moment(&self, k : i32) -> f64{
+        self.values.iter().zip(self.weights.iter()).map(|(w, p)| p * w.powi(k)).sum()
+    }
+
+    pub fn mean(&self) -> f64{
+        self.moment(1)
+    }
+
+    pub fn variance(&self) -> f64{
+        self.moment(2) - self.mean().powi(2)
+    }
+
+    pub fn characteristic(&self, u : f64) -> Complex64{
+        // G(u) = int dW P(W) e^{iuW}
+        self.values.iter().zip(self.weights.iter()).map(|(&w, &p)| Complex64::new(0f64, u * w).exp() * p).sum()
+    }
+
+    pub fn characteristic_at(&self, us : &[f64]) -> Vec<Complex64>{
+        us.iter().map(|&u| self.characteristic(u)).collect()
+    }
+
+    pub fn exp_average(&self, beta : f64) -> f64{
+        // <exp(-beta W)>, equal to 1 for a cyclic process from the Gibbs state at beta
+        self.values.iter().zip(self.weights.iter()).map(|(&w, &p)| p * (-beta * w).exp()).sum()
+    }
+
+    pub fn probability_above(&self, threshold : f64) -> f64{
+        self.values.iter().zip(self.weights.iter()).filter(|(&w, _)| w > threshold).map(|(_, &p)| p).sum()
+    }
+
+    pub fn merged(&self, tol : f64) -> Self{
+        // combine values closer than tol to their first neighbour in increasing order
+        let mut order : Vec<usize> = (0..self.values.len()).collect();
+        order.sort_by(|&a, &b| self.values[a].partial_cmp(&self.values[b]).unwrap());
+
+        let mut values : Vec<f64> = Vec::new();
+        let mut weights : Vec<f64> = Vec::new();
+        let mut start = std::f64::NEG_INFINITY;
+        for idx in order{
+            let (w, p) = (self.values[idx], self.weights[idx]);
+            if p == 0f64{
+                continue;
+            }
+            if w - start <= tol && !weights.is_empty(){
+                // weighted mean keeps the first moment
+                let last = values.len() - 1;
+                values[last] = (values[last] * weights[last] + w * p) / (weights[last] + p);
+                weights[last] += p;
+            } else {
+                values.push(w);
+                weights.push(p);
+                start = w;
+            }
+        }
+        Self{
+            values,
+            weights,
+        }
+    }
+
+    pub fn histogram(&self, bins : usize, range : (f64, f64)) -> Result<(Array1<f64>, Array1<f64>), Error>{
+        // bin centers and probability density, values outside of range are dropped
+        let (low, high) = range;
+        if bins == 0 || high <= low{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        let width = (high - low) / (bins as f64);
+        let centers : Array1<f64> = (0..bins).map(|i| low + width * (i as f64 + 0.5)).collect();
+        let mut density : Array1<f64> = Array1::zeros(bins);
+        for (&w, &p) in self.values.iter().zip(self.weights.iter()){
+            if w < low || w > high{
+                continue;
+            }
+            let i = (((w - low) / width) as usize).min(bins - 1);
+            density[i] += p / width;
+        }
+        Ok((centers, density))
+    }
+
+    pub fn broadened(&self, grid : &[f64], sigma : f64) -> Array1<f64>{
+        // gaussian broadening of every delta peak
+        let norm = 1f64 / (sigma * (2f64 * PI).sqrt());
+        grid.iter().map(|&x| {
+            self.values.iter().zip(self.weights.iter())
+                .map(|(&w, &p)| p * norm * (-(x - w).powi(2) / (2f64 * sigma * sigma)).exp())
+                .sum()
+        }).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use crate::dynamics::xxz_ising;
+    use super::*;
+
+    fn quench() -> Quench{
+        let (h0, h1) = xxz_ising(6);
+        Quench::new(h0, h1, QuenchForm::Evolve(1f64)).unwrap()
+    }
+
+    #[test]
+    fn test_work_distribution(){
+        let quench = quench();
+        let unitary = quench.unitary_eigenbasis(0.7).unwrap();
+        let change = quench.energy_change(0.7).unwrap();
+
+        for n in 0..quench.dim(){
+            let dist = WorkDistribution::eigenstate(&quench.eval0, &unitary, n).unwrap();
+            assert!((dist.total_weight() - 1f64).abs() < 1e-10);
+            assert!((dist.mean() - change[n]).abs() < 1e-10);
+            assert!((dist.characteristic(0f64) - Complex64::from(1f64)).norm() < 1e-10);
+
+            // G'(0) = i <W>
+            let du = 1e-5;
+            let derivative = (dist.characteristic(du) - dist.characteristic(-du)) / Complex64::from(2f64 * du);
+            assert!((derivative - Complex64::new(0f64, change[n])).norm() < 1e-6);
+        }
+
+        // Jarzynski equality for the cyclic process
+        let beta = 0.8;
+        let dist = WorkDistribution::thermal(&quench.eval0, &unitary, beta).unwrap();
+        assert!((dist.exp_average(beta) - 1f64).abs() < 1e-10);
+        assert!(dist.mean() >= 0f64);
+
+        let merged = dist.merged(1e-9);
+        assert!(merged.values.len() <= dist.values.len());
+        assert!((merged.mean() - dist.mean()).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_binning(){
+        let dist = WorkDistribution{ values : vec![-1.0, 0.0, 0.0, 2.0], weights : vec![0.25, 0.25, 0.25, 0.25] };
+        let (centers, density) = dist.histogram(4, (-1.0, 3.0)).unwrap();
+        close_l2(&centers, &arr1(&[-0.5, 0.5, 1.5, 2.5]), 1e-12);
+        close_l2(&density, &arr1(&[0.25, 0.5, 0.0, 0.25]), 1e-12);
+        assert!(dist.histogram(0, (0.0, 1.0)).is_err());
+
+        let grid : Vec<f64> = (0..2001).map(|i| -10.0 + 0.01 * (i as f64)).collect();
+        let broadened = dist.broadened(&grid, 0.1);
+        assert!((broadened.sum() * 0.01 - 1f64).abs() < 1e-6);
+
+        assert!((dist.probability_above(0.0) - 0.25).abs() < 1e-12);
+        close_l2(&thermal_populations(&arr1(&[0.0, 1000.0]), 1.0), &arr1(&[1.0, 0.0]), 1e-12);
+    }
+}
@@ -43,6 +43,7 @@ impl Error{
             | ErrorCode::OverFlow
             | ErrorCode::InvalidConfiguration
             | ErrorCode::InvalidArgument
+            | ErrorCode::InvalidSpec{ .. }
              => Category::Syntax,
         }
     }
@@ -96,6 +97,9 @@ pub enum ErrorCode{
 
     //
     InvalidArgument,
+
+    // Malformed model specification, with line number (0 if not related to a line)
+    InvalidSpec{ line : usize, msg : Box<str> },
 }
 
 impl Display for ErrorCode{
@@ -107,6 +111,7 @@ impl Display for ErrorCode{
             ErrorCode::OverFlow => f.write_str("State representation over flows."),
             ErrorCode::InvalidConfiguration => f.write_str("There could not be such system"),
             ErrorCode::InvalidArgument => f.write_str("Invalid Argument input"),
+            ErrorCode::InvalidSpec{ line, msg } => write!(f, "Invalid specification at line {} : {}", line, msg),
         }
     }
 }
@@ -134,6 +139,8 @@ mod tests{
             "Invalid Bit index.");
         assert_eq!(format!("{}", Error::make_error_syntax(ErrorCode::OverFlow)).as_str(),
             "State representation over flows.");
+        assert_eq!(format!("{}", Error::make_error_syntax(ErrorCode::InvalidSpec{ line : 3, msg : "unknown key".into() })).as_str(),
+            "Invalid specification at line 3 : unknown key");
     }
 
     #[test]
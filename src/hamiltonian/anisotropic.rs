@@ -0,0 +1,242 @@
+use crate::prelude::*;
+use super::complex_elements;
+
+// Terms below do not conserve EigenNumber.
+// PeriodicPairing, PeriodicXYZ and PeriodicTransverseIsing keep the parity of up-spin number,
+// so they can be diagonalized in sectors of BasisPK.
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicPairing{
+    pub gamma : f64,
+}
+
+impl PeriodicPairing{
+    pub fn new(gamma : f64) -> Self{
+        // gamma * (S+_i S+_{i+1} + S-_i S-_{i+1})
+        Self{
+            gamma,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let mut result : Vec<(usize, f64)> = Vec::new();
+        for ((i, si), (j, sj)) in state.periodic_pair_enumerate(){
+            if si == sj{
+                result.push((bit_flip_unsafe(num, i, j), self.gamma));
+            }
+        }
+
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicPairing{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicXYZ{
+    pub delta_x : f64,
+    pub delta_y : f64,
+    pub delta_z : f64,
+}
+
+impl PeriodicXYZ{
+    pub fn new(delta_x : f64, delta_y : f64, delta_z : f64) -> Self{
+        // -1/2 * sum (delta_x σx σx + delta_y σy σy + delta_z σz σz),
+        // which is same with PeriodicNearestXXZ(delta_x, delta_z) if delta_x == delta_y
+        Self{
+            delta_x,
+            delta_y,
+            delta_z,
+        }
+    }
+
+    pub fn gamma(&self) -> f64{
+        // strength of S+S+ + S-S- pairing
+        -(self.delta_x - self.delta_y) / 2f64
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let hopping = -(self.delta_x + self.delta_y) / 2f64;
+        let pairing = self.gamma();
+
+        let mut result : Vec<(usize, f64)> = Vec::new();
+        let mut sum = 0f64;
+        for ((i, si), (j, sj)) in state.periodic_pair_enumerate(){
+            let flipped = bit_flip_unsafe(num, i, j);
+            if si == sj{
+                sum -= self.delta_z / 2f64;
+                if pairing != 0f64{
+                    result.push((flipped, pairing));
+                }
+            } else {
+                sum += self.delta_z / 2f64;
+                result.push((flipped, hopping));
+            }
+        }
+
+        result.push((num, sum));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicXYZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicTransverseIsing{
+    pub j : f64,
+    pub h : f64,
+}
+
+impl PeriodicTransverseIsing{
+    pub fn new(j : f64, h : f64) -> Self{
+        // -j * sum σx_i σx_{i+1} - h * sum σz_i
+        // Ising axis is chosen along x so that the model keeps parity of up-spin number.
+        Self{
+            j,
+            h,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let length = state.length();
+        let mut result : Vec<(usize, f64)> = Vec::with_capacity(length + 1);
+
+        for ((i, _si), (j, _sj)) in state.periodic_pair_enumerate(){
+            result.push((bit_flip_unsafe(num, i, j), -self.j));
+        }
+
+        let up = state.bit_sum() as f64;
+        result.push((num, -self.h * (2f64 * up - length as f64)));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicTransverseIsing{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
+// Not a HamiltonianTerm : it does not conserve the (parity, momentum) label, and the sector builders
+// drop elements which fail to locate. Use full_matrix in the unrestricted basis instead.
+#[derive(Copy, Clone, Debug)]
+pub struct TransverseField{
+    pub h_x : f64,
+}
+
+impl TransverseField{
+    pub fn new(h_x : f64) -> Self{
+        // h_x * sum S^x_i.
+        // Single spin flip changes parity of up-spin number, so this term connects two parity sectors.
+        Self{
+            h_x,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        (0..state.length()).map(|i| (num ^ (1 << i), self.h_x / 2f64)).collect()
+    }
+
+    pub fn full_matrix(&self, length : usize) -> Array2<f64>{
+        // rows and columns are indexed by the configuration itself
+        let n = 1 << length;
+        let mut matrix : Array2<f64> = Array2::zeros((n, n));
+        for rep in 0..n{
+            for (rep2, value) in self.apply_to(&(rep, length)){
+                matrix[[rep2, rep]] += value;
+            }
+        }
+
+        return matrix;
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::{close_l2, EigValsh};
+    use super::*;
+
+    fn full_spectrum<H : HamiltonianTerm>(hamiltonian : &H, length : usize) -> Array1<f64>{
+        let n = 1 << length;
+        let mut matrix : Array2<f64> = Array2::zeros((n, n));
+        for rep in 0..n{
+            for (rep2, value) in hamiltonian.elements(rep, length){
+                matrix[[rep2, rep]] += value.re;
+            }
+        }
+        matrix.eigvalsh(UPLO::Lower).unwrap()
+    }
+
+    fn sector_spectrum<H : HamiltonianTerm>(hamiltonian : &H, length : usize) -> Array1<f64>{
+        let mut energies : Vec<f64> = Vec::new();
+        for p in 0..2{
+            for k in 0..length{
+                let egn_v = EigenParityMomentum::new(p, k);
+                let (basis, indices) = BasisPK::new(egn_v, length).build_light().unwrap();
+                let h = SectorMatrix::build(&basis, &indices, egn_v, hamiltonian);
+                energies.extend(h.eigvalsh().unwrap().iter());
+            }
+        }
+        energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        Array1::from(energies)
+    }
+
+    #[test]
+    fn test_xyz(){
+        let xyz = PeriodicXYZ::new(1.0, 1.0, 2.0);
+        let xxz = PeriodicNearestXXZ::new(1.0, 2.0);
+        for rep in 0..16{
+            assert_eq!(xyz.elements(rep, 4), xxz.elements(rep, 4));
+        }
+
+        let length = 5;
+        let xyz = PeriodicXYZ::new(1.0, 0.4, 0.7);
+        close_l2(&sector_spectrum(&xyz, length), &full_spectrum(&xyz, length), 1e-8);
+    }
+
+    #[test]
+    fn test_transverse_ising(){
+        let length = 6;
+        let tfim = PeriodicTransverseIsing::new(1.0, 0.6);
+        close_l2(&sector_spectrum(&tfim, length), &full_spectrum(&tfim, length), 1e-8);
+
+        // At critical point, ground state energy of periodic chain in even sector is
+        // -sum_k 2 |cos(k / 2)| * 2 over k = (2n + 1)π / L
+        let tfim = PeriodicTransverseIsing::new(1.0, 1.0);
+        let ground = sector_spectrum(&tfim, length)[0];
+        let truth : f64 = -(0..length).map(|n| 2f64 * ((2 * n + 1) as f64 * PI / (2 * length) as f64).cos().abs()).sum::<f64>();
+        assert!((ground - truth).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_transverse_field(){
+        let field = TransverseField::new(2.0);
+        assert_eq!(field.apply_to(&(5, 4)), vec![(4, 1.0), (7, 1.0), (1, 1.0), (13, 1.0)]);
+
+        // free spins in the full basis : h_x * sum of ±1/2
+        let spectrum = field.full_matrix(4).eigvalsh(UPLO::Lower).unwrap();
+        let truth = arr1(&[-4.0, -2.0, -2.0, -2.0, -2.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 2.0, 2.0, 2.0, 2.0, 4.0]);
+        close_l2(&spectrum, &truth, 1e-10);
+    }
+}
@@ -0,0 +1,224 @@
+use crate::prelude::*;
+
+// Terms with complex amplitudes. All of them are translationally invariant and hermitian,
+// so sector matrices from sector_hamiltonian stay hermitian.
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicDM{
+    pub d : f64,
+}
+
+impl PeriodicDM{
+    pub fn new(d : f64) -> Self{
+        // d * sum (S_i x S_{i+1})_z = d * sum (i/2) (S+_i S-_{i+1} - S-_i S+_{i+1})
+        Self{
+            d,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, Complex64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let mut result : Vec<(usize, Complex64)> = Vec::new();
+        for ((i, si), (j, sj)) in state.periodic_pair_enumerate(){
+            if si == sj{
+                continue;
+            }
+            let sign = if si == 1 {-1f64} else {1f64};
+            result.push((bit_flip_unsafe(num, i, j), Complex64::new(0f64, sign * self.d / 2f64)));
+        }
+
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicDM{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        self.apply_to(&(rep, length))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicScalarChirality{
+    pub chi : f64,
+}
+
+impl PeriodicScalarChirality{
+    pub fn new(chi : f64) -> Self{
+        // chi * sum S_i . (S_{i+1} x S_{i+2})
+        Self{
+            chi,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, Complex64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let length = state.length();
+        if length < 3{
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+
+        let spin = |i : usize| -> usize {(num >> i) % 2};
+        let mut result : Vec<(usize, Complex64)> = Vec::new();
+        for i in 0..length{
+            let sites = [i, (i + 1) % length, (i + 2) % length];
+            // S_a . (S_b x S_c) = sum over cyclic (a, b, c) of S^z_a (S_b x S_c)_z
+            for shift in 0..3{
+                let a = sites[shift];
+                let b = sites[(shift + 1) % 3];
+                let c = sites[(shift + 2) % 3];
+                if spin(b) == spin(c){
+                    continue;
+                }
+                let sz = if spin(a) == 1 {0.5} else {-0.5};
+                let sign = if spin(b) == 1 {-1f64} else {1f64};
+                result.push((bit_flip_unsafe(num, b, c), Complex64::new(0f64, sign * self.chi * sz / 2f64)));
+            }
+        }
+
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicScalarChirality{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        self.apply_to(&(rep, length))
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicFluxXXZ{
+    pub delta_x : f64,
+    pub delta_z : f64,
+    pub phi : f64,
+}
+
+impl PeriodicFluxXXZ{
+    pub fn new(delta_x : f64, delta_z : f64, phi : f64) -> Self{
+        // PeriodicNearestXXZ with gauge phase phi on every bond, i.e. total flux length * phi through the ring
+        // -delta_x * (e^{i phi} S+_i S-_{i+1} + e^{-i phi} S-_i S+_{i+1})
+        Self{
+            delta_x,
+            delta_z,
+            phi,
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, Complex64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let mut result : Vec<(usize, Complex64)> = Vec::new();
+        let mut sum = 0f64;
+        for ((i, si), (j, sj)) in state.periodic_pair_enumerate(){
+            if si == sj{
+                sum -= self.delta_z / 2f64;
+            } else {
+                sum += self.delta_z / 2f64;
+                let phase = if si == 0 {self.phi} else {-self.phi};
+                result.push((bit_flip_unsafe(num, i, j), -self.delta_x * Complex64::new(0f64, phase).exp()));
+            }
+        }
+
+        result.push((num, Complex64::from(sum)));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicFluxXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        self.apply_to(&(rep, length))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::{close_l2, EigValsh};
+    use super::*;
+
+    fn collect<H : HamiltonianTerm>(hamiltonian : &H, rep : usize, length : usize) -> FnvHashMap<usize, Complex64>{
+        let mut elements : FnvHashMap<usize, Complex64> = FnvHashMap::default();
+        for (rep2, value) in hamiltonian.elements(rep, length){
+            *elements.entry(rep2).or_insert(Complex64::from(0f64)) += value;
+        }
+        elements
+    }
+
+    #[test]
+    fn test_flux(){
+        let length = 5;
+        let flux = PeriodicFluxXXZ::new(1.0, 2.0, 0.0);
+        let xxz = PeriodicNearestXXZ::new(1.0, 2.0);
+        for rep in 0..(1 << length){
+            assert_eq!(collect(&flux, rep, length), collect(&xxz, rep, length));
+        }
+
+        // Single magnon disperses as -2 cos(q + phi)
+        let phi = 0.3;
+        let flux = PeriodicFluxXXZ::new(1.0, 2.0, phi);
+        let mut energies : Vec<f64> = Vec::new();
+        let mut truth : Vec<f64> = Vec::new();
+        for k in 0..length{
+            let egn_v = EigenNumMomentum::new(1, k);
+            let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+            let h = SectorMatrix::build(&basis, &indices, egn_v, &flux);
+            assert!(!h.is_real());
+            energies.extend(h.eigvalsh().unwrap().iter());
+            truth.push(2f64 * (4f64 - length as f64) / 2f64 - 2f64 * (2f64 * PI * (k as f64) / (length as f64) + phi).cos());
+        }
+        energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        truth.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        close_l2(&Array1::from(energies), &Array1::from(truth), 1e-10);
+    }
+
+    #[test]
+    fn test_dm(){
+        // XXZ with DM interaction is XXZ with gauge phase
+        let length = 6;
+        let (delta_x, d) = (1.0, 0.8);
+        let hamiltonian = Hamiltonian::new()
+                            .with(PeriodicNearestXXZ::new(delta_x, 0.5))
+                            .with(PeriodicDM::new(d));
+        let flux = PeriodicFluxXXZ::new((delta_x * delta_x + d * d / 4f64).sqrt(), 0.5, (-d / 2f64).atan2(delta_x));
+
+        for rep in 0..(1 << length){
+            let x = collect(&hamiltonian, rep, length);
+            let y = collect(&flux, rep, length);
+            assert_eq!(x.len(), y.len());
+            for (rep2, value) in x.iter(){
+                assert!((y.get(rep2).unwrap() - value).norm() < 1e-10);
+            }
+        }
+
+        for k in 0..length{
+            let egn_v = EigenNumMomentum::new(3, k);
+            let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+            let h = sector_hamiltonian(&basis, &indices, egn_v, &hamiltonian);
+            let h_dagger : Array2<Complex64> = conjugate(&h);
+            close_l2(&h, &h_dagger, 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_chirality(){
+        // On three sites the periodic sum has three equal cyclic copies of S_0 . (S_1 x S_2),
+        // so the eigenvalues are ±3√3/4 (twice each) and 0 (four times)
+        let length = 3;
+        let chirality = PeriodicScalarChirality::new(1.0);
+        let n = 1 << length;
+        let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+        for rep in 0..n{
+            for (rep2, value) in chirality.elements(rep, length){
+                matrix[[rep2, rep]] += value;
+            }
+        }
+
+        let c = 3f64 * 3f64.sqrt() / 4f64;
+        let eval = matrix.eigvalsh(UPLO::Lower).unwrap();
+        close_l2(&eval, &arr1(&[-c, -c, 0.0, 0.0, 0.0, 0.0, c, c]), 1e-10);
+    }
+}
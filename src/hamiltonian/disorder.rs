@@ -0,0 +1,171 @@
+use rand::{Rng, distributions::Distribution};
+use crate::prelude::*;
+use super::complex_elements;
+
+#[derive(Clone, Debug)]
+pub struct RandomField{
+    pub fields : Vec<f64>,
+}
+
+impl RandomField{
+    pub fn new(fields : Vec<f64>) -> Self{
+        Self{
+            fields,
+        }
+    }
+
+    pub fn sample<D, R>(length : usize, dist : &D, rng : &mut R) -> Self
+        where D : Distribution<f64>,
+              R : Rng{
+        Self{
+            fields : (0..length).map(|_| dist.sample(rng)).collect(),
+        }
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> f64
+        where S : State<T>,
+              T : EigenValue{
+        // sum of h_i S^z_i with S^z_i = ±1/2
+
+        if self.fields.len() != state.length(){
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+
+        let mut sum = 0f64;
+        for (&h, s) in self.fields.iter().zip(state.bit_iter()){
+            if s == 1{
+                sum += h / 2f64;
+            } else {
+                sum -= h / 2f64;
+            }
+        }
+
+        return sum;
+    }
+}
+
+impl HamiltonianTerm for RandomField{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        vec![(rep, Complex64::from(self.apply_to(&(rep, length))))]
+    }
+}
+
+// =====================================================================================================
+// =====================================================================================================
+
+#[derive(Clone, Debug)]
+pub struct RandomBondXXZ{
+    // Bond i connects site i and i + 1.
+    // Chain is periodic if there are as many bonds as sites, and open if there is one bond less.
+    pub delta_x : Vec<f64>,
+    pub delta_z : Vec<f64>,
+}
+
+impl RandomBondXXZ{
+    pub fn new(delta_x : Vec<f64>, delta_z : Vec<f64>) -> Result<Self, Error>{
+        if delta_x.len() != delta_z.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        Ok(Self{
+            delta_x,
+            delta_z,
+        })
+    }
+
+    pub fn sample<D1, D2, R>(length : usize, periodic : bool, dist_x : &D1, dist_z : &D2, rng : &mut R) -> Self
+        where D1 : Distribution<f64>,
+              D2 : Distribution<f64>,
+              R : Rng{
+        let num_bond = if periodic {length} else {length.saturating_sub(1)};
+        let delta_x = (0..num_bond).map(|_| dist_x.sample(rng)).collect();
+        let delta_z = (0..num_bond).map(|_| dist_z.sample(rng)).collect();
+
+        Self{
+            delta_x,
+            delta_z,
+        }
+    }
+
+    pub fn num_bond(&self) -> usize{
+        self.delta_x.len()
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let length = state.length();
+
+        if self.num_bond() != length && self.num_bond() + 1 != length{
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+
+        let mut result : Vec<(usize, f64)> = Vec::with_capacity(self.num_bond() + 1);
+        let mut sum = 0f64;
+        for (((i, si), (j, sj)), (&dx, &dz)) in state.periodic_pair_enumerate().zip(self.delta_x.iter().zip(self.delta_z.iter())){
+            if si == sj{
+                sum -= dz / 2f64;
+            } else {
+                sum += dz / 2f64;
+                result.push((bit_flip_unsafe(num, i, j), -dx));
+            }
+        }
+
+        result.push((num, sum));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for RandomBondXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use rand::distributions::Uniform;
+    use super::*;
+
+    #[test]
+    fn test_random_field(){
+        let field = RandomField::new(vec![1.0, 2.0, 3.0]);
+        assert_eq!(field.apply_to(&(1, 3)), -2.0);
+        assert_eq!(field.apply_to(&(6, 3)), 2.0);
+
+        let uni = Uniform::new(-1f64, 1f64);
+        let field1 = RandomField::sample(5, &uni, &mut rng_seed(3141));
+        let field2 = RandomField::sample(5, &uni, &mut rng_seed(3141));
+        assert_eq!(field1.fields, field2.fields);
+        assert!(field1.fields.iter().all(|h| h.abs() < 1.0));
+    }
+
+    #[test]
+    fn test_random_bond_sample(){
+        let uni = Uniform::new(-1f64, 1f64);
+        assert_eq!(RandomBondXXZ::sample(4, true, &uni, &uni, &mut rng_seed(3141)).num_bond(), 4);
+        assert_eq!(RandomBondXXZ::sample(4, false, &uni, &uni, &mut rng_seed(3141)).num_bond(), 3);
+        assert_eq!(RandomBondXXZ::sample(0, false, &uni, &uni, &mut rng_seed(3141)).num_bond(), 0);
+    }
+
+    #[test]
+    fn test_random_bond_xxz(){
+        let length = 3;
+        let hamiltonian = Hamiltonian::new()
+                            .with(RandomBondXXZ::new(vec![1.0, 0.5], vec![0.0, 0.0]).unwrap())
+                            .with(RandomField::new(vec![1.0, 2.0, 3.0]));
+
+        let (basis, indices) = BasisN::new(EigenNumber::new(1), length).build_light().unwrap();
+        let h = number_sector_hamiltonian_real(&basis, &indices, &hamiltonian).unwrap();
+        close_l2(&h, &arr2(&[[-2.0, -1.0, 0.0], [-1.0, -1.0, -0.5], [0.0, -0.5, 0.0]]), 1e-10);
+
+        let uniform = RandomBondXXZ::new(vec![1.0; length], vec![2.0; length]).unwrap();
+        let xxz = PeriodicNearestXXZ::new(1.0, 2.0);
+        for rep in 0..(1 << length){
+            assert_eq!(uniform.elements(rep, length), xxz.elements(rep, length));
+        }
+    }
+}
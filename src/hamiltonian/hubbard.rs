@@ -0,0 +1,139 @@
+use crate::prelude::*;
+use crate::states::hubbard::{fermion_period, fermion_translation_sign};
+use super::matrix::SectorIndex;
+
+// Build sector matrices with hubbard_sector_hamiltonian only. The generic sector builders
+// lack the fermionic translation sign, and EigenSpinNumMomentum is not BosonicTranslation.
+#[derive(Copy, Clone, Debug)]
+pub struct PeriodicHubbard{
+    pub t : f64,
+    pub u : f64,
+}
+
+impl PeriodicHubbard{
+    pub fn new(t : f64, u : f64) -> Self{
+        // -t * sum (c†_{i,σ} c_{i+1,σ} + h.c.) + u * sum n_{i,up} n_{i,down}
+        Self{
+            t,
+            u,
+        }
+    }
+
+    pub fn apply_to(&self, num : usize, length : usize) -> Vec<(usize, f64)>{
+        // num = up + (down << length), see states::hubbard
+        let mut result : Vec<(usize, f64)> = Vec::new();
+
+        for &offset in &[0, length]{
+            let species = (num >> offset) % (1 << length);
+            for i in 0..length{
+                let j = (i + 1) % length;
+                if (species >> i) % 2 == (species >> j) % 2{
+                    continue;
+                }
+
+                // fermions in between give the sign, other species is outside of [i, j]
+                let (lo, hi) = if i < j {(i, j)} else {(j, i)};
+                let between = species & ((1 << hi) - 1) & !((1 << (lo + 1)) - 1);
+                let sign = if between.count_ones() % 2 == 0 {1f64} else {-1f64};
+                result.push((num ^ ((1 << (offset + i)) | (1 << (offset + j))), -self.t * sign));
+            }
+        }
+
+        let (up, down) = (num % (1 << length), num >> length);
+        result.push((num, self.u * ((up & down).count_ones() as f64)));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicHubbard{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        super::complex_elements(self.apply_to(rep, length))
+    }
+}
+
+pub fn hubbard_sector_hamiltonian<I, H>(basis : &Vec<(usize, usize)>, indices : &I, egn_v : EigenSpinNumMomentum, hamiltonian : &H) -> Array2<Complex64>
+    where I : SectorIndex<EigenSpinNumMomentum>,
+          H : HamiltonianTerm + ?Sized{
+    // Same as sector_hamiltonian, with fermionic sign of translation from representative
+
+    let n = basis.len();
+    let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+    if n == 0{
+        return matrix;
+    }
+
+    let length = basis[0].1;
+    let omega_k = egn_v.phase_factor(length);
+    let normalize_factor = |rep : usize| -> f64 {(fermion_period(rep, length).0 as f64).sqrt() / (length as f64)};
+
+    for (idx, state) in basis.iter().enumerate(){
+        let normal_f1 = normalize_factor(state.0);
+        for (rep2, value) in hamiltonian.elements(state.0, length){
+            if let Some((idx2, d)) = indices.locate(&egn_v, rep2){
+                let rep_b = basis[idx2].0;
+                let sign = fermion_translation_sign(rep_b, d, length);
+                matrix[[idx2, idx]] += value * (sign * normal_f1 / normalize_factor(rep_b)) * omega_k.powu(d as u32);
+            }
+        }
+    }
+
+    return matrix;
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::{close_l2, EigValsh};
+    use super::*;
+
+    #[test]
+    fn test_hubbard_spectrum(){
+        // union of all momentum sectors should reproduce the spectrum in real space
+        let length = 4;
+        let hubbard = PeriodicHubbard::new(1.0, 3.0);
+
+        let n = 1 << (2 * length);
+        let mut full : Array2<f64> = Array2::zeros((n, n));
+        for rep in 0..n{
+            for (rep2, value) in hubbard.apply_to(rep, length){
+                full[[rep2, rep]] += value;
+            }
+        }
+        let truth = full.eigvalsh(UPLO::Lower).unwrap();
+
+        let mut energies : Vec<f64> = Vec::new();
+        for n_up in 0..(length + 1){
+            for n_down in 0..(length + 1){
+                for k in 0..length{
+                    let egn_v = EigenSpinNumMomentum::new(n_up, n_down, k);
+                    let (basis, indices) = match BasisHubbard::new(egn_v, length).build_light(){
+                        Ok(x) => x,
+                        Err(_) => continue,
+                    };
+                    let h = hubbard_sector_hamiltonian(&basis, &indices, egn_v, &hubbard);
+                    let h_dagger : Array2<Complex64> = conjugate(&h);
+                    close_l2(&h, &h_dagger, 1e-10);
+                    energies.extend(h.eigvalsh(UPLO::Lower).unwrap().iter());
+                }
+            }
+        }
+        energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        close_l2(&Array1::from(energies), &truth, 1e-8);
+    }
+
+    #[test]
+    fn test_free_fermion(){
+        // two up fermions on four sites fill the two lowest levels of -2t cos(q)
+        let length = 4;
+        let hubbard = PeriodicHubbard::new(1.0, 5.0);
+        let mut ground = std::f64::MAX;
+        for k in 0..length{
+            let egn_v = EigenSpinNumMomentum::new(2, 0, k);
+            if let Ok((basis, indices)) = BasisHubbard::new(egn_v, length).build_light(){
+                let h = hubbard_sector_hamiltonian(&basis, &indices, egn_v, &hubbard);
+                ground = ground.min(h.eigvalsh(UPLO::Lower).unwrap()[0]);
+            }
+        }
+        assert!((ground - (-2f64)).abs() < 1e-10);
+    }
+}
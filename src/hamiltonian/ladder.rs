@@ -0,0 +1,154 @@
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug)]
+pub struct LadderXXZ{
+    pub ladder : Ladder,
+    pub leg : (f64, f64),
+    pub rung : (f64, f64),
+}
+
+impl LadderXXZ{
+    pub fn new(ladder : Ladder, leg : (f64, f64), rung : (f64, f64)) -> Self{
+        // leg and rung couplings given as (delta_x, delta_z), legs are periodic
+        Self{
+            ladder,
+            leg,
+            rung,
+        }
+    }
+
+    pub fn bonds(&self) -> Vec<(usize, usize, (f64, f64))>{
+        let (legs, rungs) = (self.ladder.legs, self.ladder.rungs);
+        let mut bonds : Vec<(usize, usize, (f64, f64))> = Vec::new();
+
+        for w in 0..legs{
+            for x in 0..rungs{
+                bonds.push((self.ladder.site(w, x), self.ladder.site(w, (x + 1) % rungs), self.leg));
+            }
+        }
+        for w in 0..(legs.max(1) - 1){
+            for x in 0..rungs{
+                bonds.push((self.ladder.site(w, x), self.ladder.site(w + 1, x), self.rung));
+            }
+        }
+        return bonds;
+    }
+
+    pub fn apply_to(&self, num : usize) -> Vec<(usize, f64)>{
+        let mut result : Vec<(usize, f64)> = Vec::new();
+        let mut sum = 0f64;
+
+        for (i, j, (delta_x, delta_z)) in self.bonds(){
+            if (num >> i) % 2 == (num >> j) % 2{
+                sum -= delta_z / 2f64;
+            } else {
+                sum += delta_z / 2f64;
+                result.push((bit_flip_unsafe(num, i, j), -delta_x));
+            }
+        }
+
+        result.push((num, sum));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for LadderXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        if length != self.ladder.num_sites(){
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        super::complex_elements(self.apply_to(rep))
+    }
+}
+
+pub fn ladder_sector_hamiltonian<H>(gen : &BasisLadder, basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, (usize, usize, usize)>, hamiltonian : &H) -> Array2<Complex64>
+    where H : HamiltonianTerm + ?Sized{
+    // <b|H|a> = sum value * conj(χ(T^d P^s)) * sqrt(|S_b| / |S_a|), with S the stabilizer of a representative
+
+    let n = basis.len();
+    let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+    if n == 0{
+        return matrix;
+    }
+
+    let omega_k = gen.value().phase_factor(gen.ladder.rungs);
+    let exchange_sign = if gen.leg_parity == Some(1) {-1f64} else {1f64};
+    let stabilizers : Vec<f64> = basis.iter().map(|x| gen.stabilizer(x.0) as f64).collect();
+
+    for (idx, state) in basis.iter().enumerate(){
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some(&(idx2, d, s)) = indices.get(&rep2){
+                let sign = if s == 1 {exchange_sign} else {1f64};
+                let factor = sign * (stabilizers[idx2] / stabilizers[idx]).sqrt();
+                matrix[[idx2, idx]] += value * factor * omega_k.powu(d as u32);
+            }
+        }
+    }
+
+    return matrix;
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::EigValsh;
+    use super::*;
+
+    fn full_spectrum<H : HamiltonianTerm>(hamiltonian : &H, length : usize) -> Vec<f64>{
+        let n = 1 << length;
+        let mut matrix : Array2<f64> = Array2::zeros((n, n));
+        for rep in 0..n{
+            for (rep2, value) in hamiltonian.elements(rep, length){
+                matrix[[rep2, rep]] += value.re;
+            }
+        }
+        matrix.eigvalsh(UPLO::Lower).unwrap().to_vec()
+    }
+
+    fn sector_spectrum(xxz : &LadderXXZ, exchange : bool) -> Vec<f64>{
+        let ladder = xxz.ladder;
+        let mut energies : Vec<f64> = Vec::new();
+        for m in 0..=ladder.num_sites(){
+            for k in 0..ladder.rungs{
+                for q in 0..2{
+                    let egn_v = EigenNumMomentum::new(m, k);
+                    let gen = if exchange {BasisLadder::with_leg_parity(egn_v, ladder, q)} else {BasisLadder::new(egn_v, ladder)};
+                    if !exchange && q == 1{
+                        continue;
+                    }
+                    if let Ok((basis, indices)) = gen.build_light(){
+                        let h = ladder_sector_hamiltonian(&gen, &basis, &indices, xxz);
+                        energies.extend(h.eigvalsh(UPLO::Lower).unwrap().iter());
+                    }
+                }
+            }
+        }
+        energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        energies
+    }
+
+    #[test]
+    fn test_ladder_sectors(){
+        let ladder = Ladder::new(2, 4);
+        let xxz = LadderXXZ::new(ladder, (1.0, 0.7), (0.6, 1.3));
+        let truth = full_spectrum(&xxz, ladder.num_sites());
+        assert!(std::panic::catch_unwind(|| xxz.elements(0, ladder.rungs)).is_err());
+
+        for &exchange in &[false, true]{
+            let energies = sector_spectrum(&xxz, exchange);
+            assert_eq!(energies.len(), truth.len());
+            for (x, y) in energies.iter().zip(truth.iter()){
+                assert!((x - y).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rung_singlet(){
+        // decoupled antiferromagnetic rungs : singlet energy -3/2 per rung
+        let ladder = Ladder::new(2, 3);
+        let xxz = LadderXXZ::new(ladder, (0.0, 0.0), (-1.0, -1.0));
+        let energies = sector_spectrum(&xxz, true);
+        assert!((energies[0] + 4.5).abs() < 1e-10);
+    }
+}
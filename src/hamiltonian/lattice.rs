@@ -0,0 +1,145 @@
+use crate::prelude::*;
+
+#[derive(Clone, Debug)]
+pub struct LatticeXXZ{
+    pub lattice : Lattice,
+    // (label, delta_x, delta_z), bonds with other labels are not coupled
+    pub couplings : Vec<(String, f64, f64)>,
+}
+
+impl LatticeXXZ{
+    pub fn new(lattice : Lattice) -> Self{
+        Self{
+            lattice,
+            couplings : Vec::new(),
+        }
+    }
+
+    pub fn with_coupling(mut self, label : &str, delta_x : f64, delta_z : f64) -> Self{
+        self.couplings.retain(|c| c.0 != label);
+        self.couplings.push((label.to_string(), delta_x, delta_z));
+        self
+    }
+
+    pub fn coupling(&self, label : &str) -> Option<(f64, f64)>{
+        self.couplings.iter().find(|c| c.0 == label).map(|c| (c.1, c.2))
+    }
+
+    pub fn apply_to(&self, num : usize) -> Vec<(usize, f64)>{
+        let mut result : Vec<(usize, f64)> = Vec::new();
+        let mut sum = 0f64;
+
+        for (label, delta_x, delta_z) in self.couplings.iter(){
+            for bond in self.lattice.bonds_with(label){
+                if (num >> bond.i) % 2 == (num >> bond.j) % 2{
+                    sum -= delta_z / 2f64;
+                } else {
+                    sum += delta_z / 2f64;
+                    result.push((bit_flip_unsafe(num, bond.i, bond.j), -delta_x));
+                }
+            }
+        }
+
+        result.push((num, sum));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for LatticeXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        if length != self.lattice.num_sites{
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        super::complex_elements(self.apply_to(rep))
+    }
+}
+
+pub fn lattice_sector_hamiltonian<H>(lattice : &Lattice, basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, (usize, usize)>, egn_v : EigenNumMomentum, hamiltonian : &H) -> Array2<Complex64>
+    where H : HamiltonianTerm + ?Sized{
+    // Same as sector_hamiltonian, with period and phase of the translation declared in lattice
+
+    let n = basis.len();
+    let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+    if n == 0{
+        return matrix;
+    }
+
+    let order = lattice.translation_order();
+    let omega_k = egn_v.phase_factor(order);
+    let normalize_factor = |rep : usize| -> f64 {(lattice.period(rep) as f64).sqrt() / (order as f64)};
+
+    for (idx, state) in basis.iter().enumerate(){
+        let normal_f1 = normalize_factor(state.0);
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some(&(idx2, d)) = indices.get(&rep2){
+                let normal_f2 = normalize_factor(basis[idx2].0);
+                matrix[[idx2, idx]] += value * (normal_f1 / normal_f2) * omega_k.powu(d as u32);
+            }
+        }
+    }
+
+    return matrix;
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use super::*;
+
+    #[test]
+    fn test_lattice_xxz(){
+        let length = 6;
+        let xxz = LatticeXXZ::new(Lattice::chain(length, true).unwrap()).with_coupling("nn", 1.0, 2.0);
+        let truth = PeriodicNearestXXZ::new(1.0, 2.0);
+        for rep in 0..(1 << length){
+            let mut x = xxz.elements(rep, length);
+            let mut y = truth.elements(rep, length);
+            x.sort_by_key(|e| e.0);
+            y.sort_by_key(|e| e.0);
+            assert_eq!(x, y);
+        }
+        assert!(std::panic::catch_unwind(|| xxz.elements(0, length + 1)).is_err());
+
+        let lattice = Lattice::chain(length, true).unwrap();
+        for k in 0..length{
+            let egn_v = EigenNumMomentum::new(3, k);
+            let (basis, indices) = lattice.build_light_nk(egn_v).unwrap();
+            let h = lattice_sector_hamiltonian(&lattice, &basis, &indices, egn_v, &xxz);
+            close_l2(&h, &sector_hamiltonian(&basis, &indices, egn_v, &truth), 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_lattice_ladder(){
+        let ladder = Ladder::new(2, 4);
+        let lattice = Lattice::ladder(&ladder).unwrap();
+        let xxz = LatticeXXZ::new(lattice.clone()).with_coupling("leg", 1.0, 0.7).with_coupling("rung", 0.6, 1.3);
+        let truth = LadderXXZ::new(ladder, (1.0, 0.7), (0.6, 1.3));
+
+        for k in 0..4{
+            let egn_v = EigenNumMomentum::new(4, k);
+            let (basis, indices) = lattice.build_light_nk(egn_v).unwrap();
+            let h = lattice_sector_hamiltonian(&lattice, &basis, &indices, egn_v, &xxz);
+
+            let gen = BasisLadder::new(egn_v, ladder);
+            let (basis2, indices2) = gen.build_light().unwrap();
+            assert_eq!(basis, basis2);
+            close_l2(&h, &ladder_sector_hamiltonian(&gen, &basis2, &indices2, &truth), 1e-10);
+        }
+    }
+
+    #[test]
+    fn test_lattice_impurity(){
+        // open chain with one impurity bond, no translation so number sectors are used
+        let length = 5;
+        let mut lattice = Lattice::chain(length, false).unwrap();
+        lattice.bonds[2].label = "impurity".to_string();
+        let xxz = LatticeXXZ::new(lattice.clone()).with_coupling("nn", 1.0, 1.0).with_coupling("impurity", 0.3, -0.5);
+        let truth = RandomBondXXZ::new(vec![1.0, 1.0, 0.3, 1.0], vec![1.0, 1.0, -0.5, 1.0]).unwrap();
+
+        let (basis, indices) = lattice.build_light_n(EigenNumber::new(2)).unwrap();
+        let h = number_sector_hamiltonian(&basis, &indices, &xxz);
+        close_l2(&h, &number_sector_hamiltonian(&basis, &indices, &truth), 1e-10);
+    }
+}
@@ -0,0 +1,161 @@
+use crate::prelude::*;
+use super::complex_elements;
+
+#[derive(Clone, Debug)]
+pub struct PeriodicLongRangeXXZ{
+    pub delta_x : f64,
+    pub delta_z : f64,
+    // private, couplings are derived from them in new
+    alpha : f64,
+    length : usize,
+    couplings : Vec<f64>,
+}
+
+impl PeriodicLongRangeXXZ{
+    pub fn new(delta_x : f64, delta_z : f64, alpha : f64, length : usize) -> Self{
+        // couplings[r] = 1 / r^alpha for minimum image distance r on the ring
+        let mut couplings = vec![0f64; length / 2 + 1];
+        for (r, c) in couplings.iter_mut().enumerate().skip(1){
+            *c = (r as f64).powf(-alpha);
+        }
+
+        Self{
+            delta_x,
+            delta_z,
+            alpha,
+            length,
+            couplings,
+        }
+    }
+
+    pub fn alpha(&self) -> f64{
+        self.alpha
+    }
+
+    pub fn length(&self) -> usize{
+        self.length
+    }
+
+    pub fn coupling(&self, dist : usize) -> f64{
+        if self.length == 0{
+            return 0f64;
+        }
+        let dist = dist % self.length;
+        let r = dist.min(self.length - dist);
+        self.couplings[r]
+    }
+
+    pub fn apply_to<S, T>(&self, state : &S) -> Vec<(usize, f64)>
+        where S : State<T>,
+              T : EigenValue{
+        let num = state.rep();
+        let length = state.length();
+
+        if length != self.length{
+            panic!("{}", Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+
+        let mut result : Vec<(usize, f64)> = Vec::new();
+        let mut sum = 0f64;
+        for dist in 1..(length / 2 + 1){
+            let c = self.couplings[dist];
+            // i-th bit of differ is 1 when s_i != s_{i + dist}
+            let shifted = (num >> dist) + ((num % (1 << dist)) << (length - dist));
+            let differ = num ^ shifted;
+
+            // pair at half length is visited twice by (i, i + L/2) and (i + L/2, i)
+            let (num_pair, last) = if 2 * dist == length {(length / 2, length / 2)} else {(length, length)};
+            let num_anti = (differ.count_ones() as usize) * num_pair / length;
+            sum += self.delta_z * c * ((2 * num_anti) as f64 - num_pair as f64) / 2f64;
+
+            if self.delta_x == 0f64{
+                continue;
+            }
+            let mut temp = differ % (1 << last);
+            while temp > 0{
+                let i = temp.trailing_zeros() as usize;
+                let j = (i + dist) % length;
+                result.push((bit_flip_unsafe(num, i, j), -self.delta_x * c));
+                temp &= temp - 1;
+            }
+        }
+
+        result.push((num, sum));
+        return result;
+    }
+}
+
+impl HamiltonianTerm for PeriodicLongRangeXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use crate::states::bit_fns::pick_bit;
+    use super::*;
+
+    fn brute_force(num : usize, length : usize, delta_x : f64, delta_z : f64, alpha : f64) -> FnvHashMap<usize, f64>{
+        let mut result : FnvHashMap<usize, f64> = FnvHashMap::default();
+        for i in 0..length{
+            for j in (i + 1)..length{
+                let r = (j - i).min(length - j + i) as f64;
+                let c = r.powf(-alpha);
+                if pick_bit(num, length, i).unwrap() == pick_bit(num, length, j).unwrap(){
+                    *result.entry(num).or_insert(0f64) -= delta_z * c / 2f64;
+                } else {
+                    *result.entry(num).or_insert(0f64) += delta_z * c / 2f64;
+                    *result.entry(bit_flip_unsafe(num, i, j)).or_insert(0f64) -= delta_x * c;
+                }
+            }
+        }
+        return result;
+    }
+
+    #[test]
+    fn test_long_range_elements(){
+        for &length in &[5, 6]{
+            let xxz = PeriodicLongRangeXXZ::new(1.0, 2.0, 1.5, length);
+            for num in 0..(1 << length){
+                let truth = brute_force(num, length, 1.0, 2.0, 1.5);
+                let mut elements : FnvHashMap<usize, f64> = FnvHashMap::default();
+                for (rep2, value) in xxz.elements(num, length){
+                    *elements.entry(rep2).or_insert(0f64) += value.re;
+                }
+
+                assert_eq!(elements.len(), truth.len());
+                for (rep2, value) in truth.iter(){
+                    assert!((elements.get(rep2).unwrap() - value).abs() < 1e-10);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_long_range_coupling(){
+        let xxz = PeriodicLongRangeXXZ::new(1.0, 2.0, 2.0, 6);
+        assert_eq!(xxz.coupling(0), 0.0);
+        assert_eq!(xxz.coupling(2), 0.25);
+        assert_eq!(xxz.coupling(5), 1.0);
+        // distances beyond the ring wrap around
+        assert_eq!(xxz.coupling(6), 0.0);
+        assert_eq!(xxz.coupling(8), 0.25);
+        assert_eq!(xxz.coupling(13), 1.0);
+    }
+
+    #[test]
+    fn test_long_range_momentum(){
+        let length = 6;
+        let xxz = PeriodicLongRangeXXZ::new(1.0, 0.5, 2.0, length);
+        for k in 0..length{
+            let egn_v = EigenNumMomentum::new(3, k);
+            let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+            let h = sector_hamiltonian(&basis, &indices, egn_v, &xxz);
+            let h_dagger : Array2<Complex64> = conjugate(&h);
+            close_l2(&h, &h_dagger, 1e-10);
+        }
+    }
+}
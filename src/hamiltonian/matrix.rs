@@ -0,0 +1,312 @@
+use ndarray_linalg::EigValsh;
+use crate::prelude::*;
+
+pub trait SectorIndex<T : EigenValue>{
+    // Position (index in basis, translation from representative) of configuration num in sector egn_v
+    fn locate(&self, egn_v : &T, num : usize) -> Option<(usize, usize)>;
+}
+
+impl<T> SectorIndex<T> for FnvHashMap<usize, (usize, usize)>
+    where T : EigenValue{
+    fn locate(&self, _egn_v : &T, num : usize) -> Option<(usize, usize)>{
+        self.get(&num).copied()
+    }
+}
+
+impl<T> SectorIndex<T> for FnvHashMap<(T, usize), (usize, usize)>
+    where T : EigenValue{
+    fn locate(&self, egn_v : &T, num : usize) -> Option<(usize, usize)>{
+        self.get(&(*egn_v, num)).copied()
+    }
+}
+
+impl<T> SectorIndex<T> for FnvHashMap<Representation<T>, (usize, usize)>
+    where T : EigenValue{
+    fn locate(&self, egn_v : &T, num : usize) -> Option<(usize, usize)>{
+        self.get(&Representation(*egn_v, num)).copied()
+    }
+}
+
+pub fn normalize_factor(state : &(usize, usize)) -> f64{
+    let p = period_unsafe(state.0, state.1);
+    (p as f64).sqrt() / (state.1 as f64)
+}
+
+pub fn sector_hamiltonian<T, I, H>(basis : &Vec<(usize, usize)>, indices : &I, egn_v : T, hamiltonian : &H) -> Array2<Complex64>
+    where T : BosonicTranslation,
+          I : SectorIndex<T>,
+          H : HamiltonianTerm + ?Sized{
+
+    let n = basis.len();
+    let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+    if n == 0{
+        return matrix;
+    }
+
+    let omega_k = egn_v.phase_factor(basis[0].1);
+    for (idx, state) in basis.iter().enumerate(){
+        let normal_f1 = normalize_factor(state);
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some((idx2, d)) = indices.locate(&egn_v, rep2){
+                let normal_f2 = normalize_factor(&basis[idx2]);
+                matrix[[idx2, idx]] += value * (normal_f1 / normal_f2) * omega_k.powu(d as u32);
+            }
+        }
+    }
+
+    return matrix;
+}
+
+pub fn sector_hamiltonian_real<T, I, H>(basis : &Vec<(usize, usize)>, indices : &I, egn_v : T, hamiltonian : &H) -> Result<Array2<f64>, Error>
+    where T : BosonicTranslation,
+          I : SectorIndex<T>,
+          H : HamiltonianTerm + ?Sized{
+
+    let n = basis.len();
+    let mut matrix : Array2<f64> = Array2::zeros((n, n));
+    if n == 0{
+        return Ok(matrix);
+    }
+
+    let length = basis[0].1;
+    if !egn_v.is_real_phase(length){
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+
+    for (idx, state) in basis.iter().enumerate(){
+        let normal_f1 = normalize_factor(state);
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some((idx2, d)) = indices.locate(&egn_v, rep2){
+                if value.im != 0f64{
+                    return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+                }
+                let normal_f2 = normalize_factor(&basis[idx2]);
+                // phase factor is ±1, so only the parity of d matters when k != 0
+                let sign = if egn_v.wave_number() != 0 && d % 2 == 1 {-1f64} else {1f64};
+                matrix[[idx2, idx]] += sign * value.re * normal_f1 / normal_f2;
+            }
+        }
+    }
+
+    return Ok(matrix);
+}
+
+pub fn number_sector_hamiltonian<H>(basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, usize>, hamiltonian : &H) -> Array2<Complex64>
+    where H : HamiltonianTerm + ?Sized{
+    // Hamiltonian in a sector of BasisN, which does not need translational symmetry
+
+    let n = basis.len();
+    let mut matrix : Array2<Complex64> = Array2::zeros((n, n));
+
+    for (idx, state) in basis.iter().enumerate(){
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some(&idx2) = indices.get(&rep2){
+                matrix[[idx2, idx]] += value;
+            }
+        }
+    }
+
+    return matrix;
+}
+
+pub fn number_sector_hamiltonian_real<H>(basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, usize>, hamiltonian : &H) -> Result<Array2<f64>, Error>
+    where H : HamiltonianTerm + ?Sized{
+
+    let n = basis.len();
+    let mut matrix : Array2<f64> = Array2::zeros((n, n));
+
+    for (idx, state) in basis.iter().enumerate(){
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some(&idx2) = indices.get(&rep2){
+                if value.im != 0f64{
+                    return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+                }
+                matrix[[idx2, idx]] += value.re;
+            }
+        }
+    }
+
+    return Ok(matrix);
+}
+
+// =====================================================================================================
+// =====================================================================================================
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SectorMatrix{
+    Real(Array2<f64>),
+    Complex(Array2<Complex64>),
+}
+
+impl SectorMatrix{
+    pub fn build<T, I, H>(basis : &Vec<(usize, usize)>, indices : &I, egn_v : T, hamiltonian : &H) -> Self
+        where T : BosonicTranslation,
+              I : SectorIndex<T>,
+              H : HamiltonianTerm + ?Sized{
+        // Choose real storage whenever phase factors of the sector are ±1 and all amplitudes are real
+
+        match sector_hamiltonian_real(basis, indices, egn_v, hamiltonian){
+            Ok(h) => SectorMatrix::Real(h),
+            Err(_) => SectorMatrix::Complex(sector_hamiltonian(basis, indices, egn_v, hamiltonian)),
+        }
+    }
+
+    pub fn build_number<H>(basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, usize>, hamiltonian : &H) -> Self
+        where H : HamiltonianTerm + ?Sized{
+        match number_sector_hamiltonian_real(basis, indices, hamiltonian){
+            Ok(h) => SectorMatrix::Real(h),
+            Err(_) => SectorMatrix::Complex(number_sector_hamiltonian(basis, indices, hamiltonian)),
+        }
+    }
+
+    pub fn is_real(&self) -> bool{
+        match self{
+            SectorMatrix::Real(_) => true,
+            SectorMatrix::Complex(_) => false,
+        }
+    }
+
+    pub fn dim(&self) -> usize{
+        match self{
+            SectorMatrix::Real(h) => h.nrows(),
+            SectorMatrix::Complex(h) => h.nrows(),
+        }
+    }
+
+    pub fn to_complex(&self) -> Array2<Complex64>{
+        match self{
+            SectorMatrix::Real(h) => h.map(|&x| Complex64::from(x)),
+            SectorMatrix::Complex(h) => h.clone(),
+        }
+    }
+
+    pub fn into_complex(self) -> Array2<Complex64>{
+        match self{
+            SectorMatrix::Real(h) => h.map(|&x| Complex64::from(x)),
+            SectorMatrix::Complex(h) => h,
+        }
+    }
+
+    pub fn scaled_add(&self, r : f64, other : &SectorMatrix) -> Result<SectorMatrix, Error>{
+        // self + r * other, staying real only if both are real
+        if self.dim() != other.dim(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        match (self, other){
+            (SectorMatrix::Real(h0), SectorMatrix::Real(h1)) => Ok(SectorMatrix::Real(h1 * r + h0)),
+            (SectorMatrix::Complex(h0), SectorMatrix::Complex(h1)) => Ok(SectorMatrix::Complex(h1 * Complex64::from(r) + h0)),
+            _ => Ok(SectorMatrix::Complex(other.to_complex() * Complex64::from(r) + self.to_complex())),
+        }
+    }
+
+    pub fn adjoint_dot(&self, v : &Array1<Complex64>) -> Array1<Complex64>{
+        // self† v, real storage acts on real and imaginary parts separately
+        match self{
+            SectorMatrix::Real(h) => {
+                let re = h.t().dot(&v.mapv(|z| z.re));
+                let im = h.t().dot(&v.mapv(|z| z.im));
+                re.iter().zip(im.iter()).map(|(&x, &y)| Complex64::new(x, y)).collect()
+            },
+            SectorMatrix::Complex(h) => {
+                let conj_h : Array2<Complex64> = conjugate(h);
+                conj_h.dot(v)
+            },
+        }
+    }
+
+    pub fn sandwich(&self, op : &Array2<Complex64>) -> Array2<Complex64>{
+        // self† op self, e.g. an operator in the eigenbasis when self holds eigenvectors as columns
+        match self{
+            SectorMatrix::Real(h) => {
+                let re = h.t().dot(&op.mapv(|z| z.re)).dot(h);
+                let im = h.t().dot(&op.mapv(|z| z.im)).dot(h);
+                ndarray::Zip::from(&re).and(&im).map_collect(|&x, &y| Complex64::new(x, y))
+            },
+            SectorMatrix::Complex(h) => {
+                let conj_h : Array2<Complex64> = conjugate(h);
+                conj_h.dot(op).dot(h)
+            },
+        }
+    }
+
+    pub fn diag_sandwich(&self, d : &Array1<Complex64>) -> Array2<Complex64>{
+        // self diag(d) self†, e.g. a function of the matrix from its eigenvectors
+        match self{
+            SectorMatrix::Real(h) => {
+                let re = (h * &d.mapv(|z| z.re)).dot(&h.t());
+                let im = (h * &d.mapv(|z| z.im)).dot(&h.t());
+                ndarray::Zip::from(&re).and(&im).map_collect(|&x, &y| Complex64::new(x, y))
+            },
+            SectorMatrix::Complex(h) => {
+                let conj_h : Array2<Complex64> = conjugate(h);
+                (h * d).dot(&conj_h)
+            },
+        }
+    }
+
+    pub fn eigvalsh(&self) -> Result<Array1<f64>, Error>{
+        match self{
+            SectorMatrix::Real(h) => h.eigvalsh(UPLO::Lower),
+            SectorMatrix::Complex(h) => h.eigvalsh(UPLO::Lower),
+        }.map_err(|c| Error::make_error_msg(format_args!("{}", c).to_string()))
+    }
+
+    pub fn eigh(&self) -> Result<(Array1<f64>, SectorMatrix), Error>{
+        // Eigenvectors are returned with the same storage as the matrix
+        match self{
+            SectorMatrix::Real(h) => h.eigh(UPLO::Lower).map(|(e, v)| (e, SectorMatrix::Real(v))),
+            SectorMatrix::Complex(h) => h.eigh(UPLO::Lower).map(|(e, v)| (e, SectorMatrix::Complex(v))),
+        }.map_err(|c| Error::make_error_msg(format_args!("{}", c).to_string()))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use super::*;
+
+    #[test]
+    fn test_sector_hamiltonian(){
+        let length = 4;
+        let egn_v = EigenNumMomentum::new(2, 0);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+
+        let truth : Array2<f64> = arr2(&[[0.0, -2f64 * 2f64.sqrt()], [-2f64 * 2f64.sqrt(), 4.0]]);
+        let h = sector_hamiltonian(&basis, &indices, egn_v, &xxz);
+        close_l2(&h, &truth.map(|&x| Complex64::from(x)), 1e-10);
+
+        let h = sector_hamiltonian_real(&basis, &indices, egn_v, &xxz).unwrap();
+        close_l2(&h, &truth, 1e-10);
+
+        let egn_v = EigenNumMomentum::new(2, 2);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let h = sector_hamiltonian_real(&basis, &indices, egn_v, &xxz).unwrap();
+        close_l2(&h, &arr2(&[[0.0, 0.0], [0.0, 4.0]]), 1e-10);
+
+        let egn_v = EigenNumMomentum::new(2, 1);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        assert_eq!(sector_hamiltonian_real(&basis, &indices, egn_v, &xxz),
+            Err(Error::make_error_syntax(ErrorCode::InvalidArgument)));
+    }
+
+    #[test]
+    fn test_sector_matrix(){
+        let length = 4;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+        let (basis_map, indices) = Basis::new(length).build_light_nk();
+
+        let egn_v = EigenNumMomentum::new(2, 0);
+        let h = SectorMatrix::build(basis_map.get(&egn_v).unwrap(), &indices, egn_v, &xxz);
+        assert!(h.is_real());
+        assert_eq!(h.dim(), 2);
+        let eval = h.eigvalsh().unwrap();
+        close_l2(&eval, &arr1(&[2f64 - 12f64.sqrt(), 2f64 + 12f64.sqrt()]), 1e-10);
+
+        let egn_v = EigenNumMomentum::new(1, 1);
+        let h = SectorMatrix::build(basis_map.get(&egn_v).unwrap(), &indices, egn_v, &xxz);
+        assert!(!h.is_real());
+    }
+}
@@ -1,8 +1,69 @@
 #[allow(unused_imports)]
 use std::hash::Hash;
 use genawaiter::{sync::gen, yield_};
+use dyn_clone::DynClone;
 use crate::prelude::*;
 
+pub mod matrix;
+pub mod sparse;
+pub mod disorder;
+pub mod long_range;
+pub mod anisotropic;
+pub mod chiral;
+pub mod hubbard;
+pub mod ladder;
+pub mod lattice;
+
+pub trait HamiltonianTerm : DynClone + Send + Sync{
+    // Matrix elements <rep2|H|rep> as (rep2, value), diagonal element included
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>;
+}
+
+pub(crate) fn complex_elements<I>(elements : I) -> Vec<(usize, Complex64)>
+    where I : IntoIterator<Item = (usize, f64)>{
+    elements.into_iter().map(|(n, x)| (n, Complex64::from(x))).collect()
+}
+
+dyn_clone::clone_trait_object!(HamiltonianTerm);
+
+#[derive(Clone)]
+pub struct Hamiltonian{
+    pub terms : Vec<Box<dyn HamiltonianTerm>>,
+}
+
+impl Hamiltonian{
+    pub fn new() -> Self{
+        Self{
+            terms : Vec::new(),
+        }
+    }
+
+    pub fn with<H>(mut self, term : H) -> Self
+        where H : HamiltonianTerm + 'static{
+        self.terms.push(Box::new(term));
+        self
+    }
+
+    pub fn push<H>(&mut self, term : H)
+        where H : HamiltonianTerm + 'static{
+        self.terms.push(Box::new(term));
+    }
+
+    pub fn len(&self) -> usize{
+        self.terms.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.terms.is_empty()
+    }
+}
+
+impl HamiltonianTerm for Hamiltonian{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        self.terms.iter().flat_map(|term| term.elements(rep, length)).collect()
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PeriodicIsing{
     pub delta : f64,
@@ -32,6 +93,12 @@ impl PeriodicIsing{
     }
 }
 
+impl HamiltonianTerm for PeriodicIsing{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        vec![(rep, Complex64::from(self.apply_to(&(rep, length))))]
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PeriodicNearestXXZ{
     pub delta_x : f64,
@@ -67,6 +134,12 @@ impl PeriodicNearestXXZ{
     }
 }
 
+impl HamiltonianTerm for PeriodicNearestXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
 #[derive(Copy, Clone, Debug)]
 pub struct PeriodicNextNearestXXZ{
     pub delta_x1 : f64,
@@ -111,10 +184,17 @@ impl PeriodicNextNearestXXZ{
     }
 }
 
+impl HamiltonianTerm for PeriodicNextNearestXXZ{
+    fn elements(&self, rep : usize, length : usize) -> Vec<(usize, Complex64)>{
+        complex_elements(self.apply_to(&(rep, length)))
+    }
+}
+
 
 pub fn prepare_energy_map<V>(index : V, energies : &Array1<f64>, unit : f64) -> FnvHashMap<i128, Vec<(V, usize)>>
     where V : EigenValue + Clone{
     // Prepare hashmap which will be used for degeneracy check
+    // Buckets depend on insertion order, spectrum::clustering gives order independent clusters
 
     let mut energy_map : FnvHashMap<i128, Vec<(V, usize)>> = FnvHashMap::default();
     for (idx, &e) in energies.iter().enumerate(){
@@ -182,6 +262,7 @@ pub fn count_degeneracy_from<V>(energy_map : &mut FnvHashMap<i128, Vec<(V, usize
 
 pub fn degeneracy_pair<V>(length : usize, energy_map : &FnvHashMap<i128, Vec<(V, usize)>>) -> Result<(Array1<f64>, FnvHashMap<V, Vec<(usize, usize)>>), Error>
     where V : EigenValue + Hash + Eq{
+    // counts : 1/degeneracy for representatives and -1 for skipped levels, see spectrum::degeneracy for a typed version
     let mut pair_map : FnvHashMap<V, Vec<(usize, usize)>> = FnvHashMap::default();
     let mut counts : Array1<f64> = Array1::ones(length);
 
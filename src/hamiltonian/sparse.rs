@@ -0,0 +1,210 @@
+use crate::prelude::*;
+use super::matrix::{SectorIndex, normalize_factor};
+
+pub trait MatVec{
+    fn dim(&self) -> usize;
+
+    fn matvec(&self, v : &Array1<Complex64>) -> Array1<Complex64>;
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SparseMatrix{
+    pub dim : usize,
+    // compressed sparse rows
+    pub indptr : Vec<usize>,
+    pub indices : Vec<usize>,
+    pub values : Vec<Complex64>,
+}
+
+impl SparseMatrix{
+    pub fn from_triplets(dim : usize, mut triplets : Vec<(usize, usize, Complex64)>) -> Result<Self, Error>{
+        // (row, column, value), repeated entries are summed
+        if triplets.iter().any(|x| x.0 >= dim || x.1 >= dim){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        triplets.sort_by_key(|x| (x.0, x.1));
+
+        let mut indptr : Vec<usize> = vec![0; dim + 1];
+        let mut indices : Vec<usize> = Vec::with_capacity(triplets.len());
+        let mut values : Vec<Complex64> = Vec::with_capacity(triplets.len());
+        let mut last : Option<(usize, usize)> = None;
+
+        for (row, col, value) in triplets{
+            if last == Some((row, col)){
+                *values.last_mut().unwrap() += value;
+                continue;
+            }
+            indices.push(col);
+            values.push(value);
+            indptr[row + 1] += 1;
+            last = Some((row, col));
+        }
+        for i in 0..dim{
+            indptr[i + 1] += indptr[i];
+        }
+
+        Ok(Self{
+            dim,
+            indptr,
+            indices,
+            values,
+        })
+    }
+
+    pub fn from_dense(matrix : &Array2<Complex64>, tol : f64) -> Result<Self, Error>{
+        if matrix.nrows() != matrix.ncols(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let triplets = matrix.indexed_iter()
+                        .filter(|(_, x)| x.norm() > tol)
+                        .map(|((i, j), &x)| (i, j, x))
+                        .collect();
+        Self::from_triplets(matrix.nrows(), triplets)
+    }
+
+    pub fn nnz(&self) -> usize{
+        self.values.len()
+    }
+
+    pub fn to_dense(&self) -> Array2<Complex64>{
+        let mut matrix : Array2<Complex64> = Array2::zeros((self.dim, self.dim));
+        for row in 0..self.dim{
+            for p in self.indptr[row]..self.indptr[row + 1]{
+                matrix[[row, self.indices[p]]] += self.values[p];
+            }
+        }
+        matrix
+    }
+}
+
+impl MatVec for SparseMatrix{
+    fn dim(&self) -> usize{
+        self.dim
+    }
+
+    fn matvec(&self, v : &Array1<Complex64>) -> Array1<Complex64>{
+        let mut result : Array1<Complex64> = Array1::zeros(self.dim);
+        for row in 0..self.dim{
+            let mut sum = Complex64::from(0f64);
+            for p in self.indptr[row]..self.indptr[row + 1]{
+                sum += self.values[p] * v[self.indices[p]];
+            }
+            result[row] = sum;
+        }
+        result
+    }
+}
+
+impl MatVec for Array2<Complex64>{
+    fn dim(&self) -> usize{
+        self.nrows()
+    }
+
+    fn matvec(&self, v : &Array1<Complex64>) -> Array1<Complex64>{
+        self.dot(v)
+    }
+}
+
+impl MatVec for SectorMatrix{
+    fn dim(&self) -> usize{
+        SectorMatrix::dim(self)
+    }
+
+    fn matvec(&self, v : &Array1<Complex64>) -> Array1<Complex64>{
+        match self{
+            SectorMatrix::Real(h) => {
+                // real and imaginary parts separately, without a complex copy of h
+                let re = h.dot(&v.mapv(|z| z.re));
+                let im = h.dot(&v.mapv(|z| z.im));
+                re.iter().zip(im.iter()).map(|(&x, &y)| Complex64::new(x, y)).collect()
+            },
+            SectorMatrix::Complex(h) => h.dot(v),
+        }
+    }
+}
+
+pub fn sparse_sector_hamiltonian<T, I, H>(basis : &Vec<(usize, usize)>, indices : &I, egn_v : T, hamiltonian : &H) -> SparseMatrix
+    where T : BosonicTranslation,
+          I : SectorIndex<T>,
+          H : HamiltonianTerm + ?Sized{
+    // Same elements as sector_hamiltonian without allocating the dense matrix
+    let n = basis.len();
+    let mut triplets : Vec<(usize, usize, Complex64)> = Vec::new();
+    if n > 0{
+        let omega_k = egn_v.phase_factor(basis[0].1);
+        for (idx, state) in basis.iter().enumerate(){
+            let normal_f1 = normalize_factor(state);
+            for (rep2, value) in hamiltonian.elements(state.0, state.1){
+                if let Some((idx2, d)) = indices.locate(&egn_v, rep2){
+                    let normal_f2 = normalize_factor(&basis[idx2]);
+                    triplets.push((idx2, idx, value * (normal_f1 / normal_f2) * omega_k.powu(d as u32)));
+                }
+            }
+        }
+    }
+
+    match SparseMatrix::from_triplets(n, triplets){
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+pub fn sparse_number_sector_hamiltonian<H>(basis : &Vec<(usize, usize)>, indices : &FnvHashMap<usize, usize>, hamiltonian : &H) -> SparseMatrix
+    where H : HamiltonianTerm + ?Sized{
+
+    let mut triplets : Vec<(usize, usize, Complex64)> = Vec::new();
+    for (idx, state) in basis.iter().enumerate(){
+        for (rep2, value) in hamiltonian.elements(state.0, state.1){
+            if let Some(&idx2) = indices.get(&rep2){
+                triplets.push((idx2, idx, value));
+            }
+        }
+    }
+
+    match SparseMatrix::from_triplets(basis.len(), triplets){
+        Ok(x) => x,
+        Err(e) => panic!("{}", e),
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn xxz_test_sector(egn_v : EigenNumMomentum, length : usize) -> (SparseMatrix, Array1<f64>, Array2<Complex64>){
+    // sparse XXZ(1, 0.5) in a momentum sector with its dense eigen decomposition, shared by tests of Krylov methods
+    let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+    let xxz = PeriodicNearestXXZ::new(1f64, 0.5f64);
+    let (eval, evec) = sector_hamiltonian(&basis, &indices, egn_v, &xxz).eigh(UPLO::Lower).unwrap();
+    (sparse_sector_hamiltonian(&basis, &indices, egn_v, &xxz), eval, evec)
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use super::*;
+
+    #[test]
+    fn test_sparse_sector(){
+        let length = 8;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+        for k in 0..length{
+            let egn_v = EigenNumMomentum::new(4, k);
+            let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+            let dense = sector_hamiltonian(&basis, &indices, egn_v, &xxz);
+            let sparse = sparse_sector_hamiltonian(&basis, &indices, egn_v, &xxz);
+            close_l2(&sparse.to_dense(), &dense, 1e-10);
+
+            let v : Array1<Complex64> = (0..basis.len()).map(|i| Complex64::new(i as f64, 1.0)).collect();
+            close_l2(&sparse.matvec(&v), &dense.dot(&v), 1e-10);
+
+            // real storage at k = 0 and k = L/2 acts on complex vectors
+            let matrix = SectorMatrix::build(&basis, &indices, egn_v, &xxz);
+            assert_eq!(matrix.is_real(), 2 * k % length == 0);
+            close_l2(&matrix.matvec(&v), &dense.dot(&v), 1e-10);
+        }
+
+        let sparse = SparseMatrix::from_triplets(2, vec![(0, 1, Complex64::from(1.0)), (0, 1, Complex64::from(2.0)), (1, 0, Complex64::from(3.0))]).unwrap();
+        assert_eq!(sparse.nnz(), 2);
+        assert_eq!(sparse.to_dense(), arr2(&[[0.0, 3.0], [3.0, 0.0]]).map(|&x| Complex64::from(x)));
+        assert!(SparseMatrix::from_triplets(2, vec![(2, 0, Complex64::from(1.0))]).is_err());
+    }
+}
@@ -0,0 +1,254 @@
+use crate::prelude::*;
+use crate::states::bit_fns::sum_bit;
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Bond{
+    pub i : usize,
+    pub j : usize,
+    pub label : String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Lattice{
+    pub num_sites : usize,
+    pub bonds : Vec<Bond>,
+    // site i is moved to translation[i], None if the lattice has no declared translation
+    pub translation : Option<Vec<usize>>,
+}
+
+impl Lattice{
+    pub fn new(num_sites : usize) -> Self{
+        Self{
+            num_sites,
+            bonds : Vec::new(),
+            translation : None,
+        }
+    }
+
+    pub fn add_bond(&mut self, i : usize, j : usize, label : &str) -> Result<(), Error>{
+        if i >= self.num_sites || j >= self.num_sites{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidBitIndex));
+        }
+        if i == j{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        self.bonds.push(Bond{ i, j, label : label.to_string() });
+        return Ok(());
+    }
+
+    pub fn with_bond(mut self, i : usize, j : usize, label : &str) -> Result<Self, Error>{
+        self.add_bond(i, j, label)?;
+        Ok(self)
+    }
+
+    pub fn with_translation(mut self, translation : Vec<usize>) -> Result<Self, Error>{
+        // translation should be a permutation of sites mapping every bond to a bond with the same label
+        let n = self.num_sites;
+        let mut seen = vec![false; n];
+        if translation.len() != n{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        for &t in translation.iter(){
+            if t >= n || seen[t]{
+                return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+            }
+            seen[t] = true;
+        }
+
+        let key = |i : usize, j : usize, label : &str| (i.min(j), i.max(j), label.to_string());
+        let mut bonds : Vec<(usize, usize, String)> = self.bonds.iter().map(|b| key(b.i, b.j, &b.label)).collect();
+        let mut images : Vec<(usize, usize, String)> = self.bonds.iter().map(|b| key(translation[b.i], translation[b.j], &b.label)).collect();
+        bonds.sort();
+        images.sort();
+        if bonds != images{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+
+        self.translation = Some(translation);
+        Ok(self)
+    }
+
+    pub fn chain(length : usize, periodic : bool) -> Result<Self, Error>{
+        // bonds (i, i+1) labeled "nn", translation i -> i-1 as cyclic_move when periodic
+        // periodic chain of one site would have a bond to itself, which add_bond rejects
+        let mut lattice = Self::new(length);
+        let num_bond = if periodic {length} else {length.max(1) - 1};
+        for i in 0..num_bond{
+            lattice.add_bond(i, (i + 1) % length, "nn")?;
+        }
+        if periodic{
+            lattice.translation = Some((0..length).map(|i| (i + length - 1) % length).collect());
+        }
+        Ok(lattice)
+    }
+
+    pub fn ladder(ladder : &Ladder) -> Result<Self, Error>{
+        // bonds labeled "leg" and "rung", translation along the legs as Ladder::translate
+        let (legs, rungs) = (ladder.legs, ladder.rungs);
+        let mut lattice = Self::new(ladder.num_sites());
+        for w in 0..legs{
+            for x in 0..rungs{
+                lattice.add_bond(ladder.site(w, x), ladder.site(w, (x + 1) % rungs), "leg")?;
+            }
+        }
+        for w in 0..(legs.max(1) - 1){
+            for x in 0..rungs{
+                lattice.add_bond(ladder.site(w, x), ladder.site(w + 1, x), "rung")?;
+            }
+        }
+        lattice.translation = Some((0..ladder.num_sites()).map(|s| ladder.site(s / rungs, (s % rungs + rungs - 1) % rungs)).collect());
+        Ok(lattice)
+    }
+
+    pub fn labels(&self) -> Vec<&str>{
+        let mut labels : Vec<&str> = Vec::new();
+        for b in self.bonds.iter(){
+            if !labels.contains(&b.label.as_str()){
+                labels.push(b.label.as_str());
+            }
+        }
+        labels
+    }
+
+    pub fn bonds_with<'a>(&'a self, label : &'a str) -> impl Iterator<Item = &'a Bond> + 'a{
+        self.bonds.iter().filter(move |b| b.label == label)
+    }
+
+    pub fn has_translation(&self) -> bool{
+        self.translation.is_some()
+    }
+
+    pub fn translate(&self, num : usize) -> usize{
+        match &self.translation{
+            None => num,
+            Some(t) => {
+                let mut result = 0;
+                for (i, &ti) in t.iter().enumerate(){
+                    result |= ((num >> i) & 1) << ti;
+                }
+                result
+            },
+        }
+    }
+
+    pub fn translation_order(&self) -> usize{
+        // smallest n with T^n = identity on sites
+        match &self.translation{
+            None => 1,
+            Some(t) => {
+                let mut order = 1;
+                let mut current = t.clone();
+                while current.iter().enumerate().any(|(i, &x)| i != x){
+                    current = current.iter().map(|&x| t[x]).collect();
+                    order += 1;
+                }
+                order
+            },
+        }
+    }
+
+    pub fn period(&self, num : usize) -> usize{
+        let mut temp = self.translate(num);
+        let mut p = 1;
+        while temp != num{
+            temp = self.translate(temp);
+            p += 1;
+        }
+        p
+    }
+
+    pub fn build_light_n(&self, egn_v : EigenNumber) -> Result<(Vec<(usize, usize)>, FnvHashMap<usize, usize>), Error>{
+        BasisN::new(egn_v, self.num_sites).build_light()
+    }
+
+    pub fn build_light_nk(&self, egn_v : EigenNumMomentum) -> Result<(Vec<(usize, usize)>, FnvHashMap<usize, (usize, usize)>), Error>{
+        // momentum sector of the declared translation, k in units of 2π / translation_order
+        if !self.has_translation(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        if egn_v.total_number() > self.num_sites{
+            return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+        }
+
+        let order = self.translation_order();
+        let mut basis : Vec<(usize, usize)> = Vec::new();
+        let mut indices : FnvHashMap<usize, (usize, usize)> = FnvHashMap::default();
+        let mut idx = 0;
+
+        for n in 0..(1 << self.num_sites){
+            if sum_bit(n) != egn_v.total_number(){
+                continue;
+            }
+
+            let p = self.period(n);
+            let mut temp = self.translate(n);
+            let mut is_rep = true;
+            for _ in 1..p{
+                is_rep &= temp > n;
+                temp = self.translate(temp);
+            }
+            if !is_rep || !egn_v.check_commensurability(p, order){
+                continue;
+            }
+
+            let mut temp = n;
+            for d in 0..p{
+                indices.insert(temp, (idx, d));
+                temp = self.translate(temp);
+            }
+            basis.push((n, self.num_sites));
+            idx += 1;
+        }
+
+        if basis.len() == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration));
+        }
+        return Ok((basis, indices));
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lattice(){
+        let chain = Lattice::chain(4, true).unwrap();
+        assert_eq!(chain.bonds.len(), 4);
+        assert_eq!(chain.translation_order(), 4);
+        for n in 0..16{
+            assert_eq!(chain.translate(n), cyclic_move_unsafe(n, 4));
+        }
+
+        let ladder = Ladder::new(2, 3);
+        let lattice = Lattice::ladder(&ladder).unwrap();
+        assert_eq!(lattice.labels(), vec!["leg", "rung"]);
+        assert_eq!(lattice.bonds_with("rung").count(), 3);
+        for n in 0..64{
+            assert_eq!(lattice.translate(n), ladder.translate(n));
+        }
+
+        assert_eq!(Lattice::new(3).with_bond(0, 3, "nn"), Err(Error::make_error_syntax(ErrorCode::InvalidBitIndex)));
+        // a single periodic site or rung would be bonded to itself
+        assert_eq!(Lattice::chain(1, true), Err(Error::make_error_syntax(ErrorCode::InvalidArgument)));
+        assert_eq!(Lattice::chain(1, false).unwrap().bonds.len(), 0);
+        assert!(Lattice::ladder(&Ladder::new(2, 1)).is_err());
+        let open = Lattice::chain(4, false).unwrap();
+        assert_eq!(open.clone().with_translation(vec![3, 0, 1, 2]), Err(Error::make_error_syntax(ErrorCode::InvalidConfiguration)));
+        assert_eq!(open.with_translation(vec![0, 0, 1, 2]), Err(Error::make_error_syntax(ErrorCode::InvalidArgument)));
+    }
+
+    #[test]
+    fn test_lattice_basis(){
+        let lattice = Lattice::chain(4, true).unwrap();
+        let egn_v = EigenNumMomentum::new(2, 0);
+        let (basis, indices) = lattice.build_light_nk(egn_v).unwrap();
+        let (truth_basis, truth_indices) = BasisNK::new(egn_v, 4).build_light().unwrap();
+        assert_eq!(basis, truth_basis);
+        assert_eq!(indices, truth_indices);
+
+        assert!(Lattice::chain(4, false).unwrap().build_light_nk(egn_v).is_err());
+    }
+}
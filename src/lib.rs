@@ -4,7 +4,13 @@
 pub mod error;
 pub mod states;
 pub mod bases;
+pub mod lattice;
 pub mod hamiltonian;
+pub mod dynamics;
+pub mod spectrum;
+pub mod random;
+pub mod spec;
+pub mod thermal;
 
 
 pub mod prelude;
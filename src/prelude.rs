@@ -16,20 +16,53 @@ pub use crate::{
     error::{Error, ErrorCode},
     states::{
         State, SimpleState, EigenState,
-        representation::{Representation, RepNum, RepNumMomentum},
-        symmetry::{EigenValue, HigherThan, LowerThan, EmptyValue, NumberConservation, EigenNumber, TranslationalSymmetry, EigenNumMomentum},
+        representation::{Representation, RepNum, RepNumMomentum, RepParityMomentum, RepSpinNumMomentum},
+        symmetry::{EigenValue, HigherThan, LowerThan, EmptyValue, NumberConservation, EigenNumber, TranslationalSymmetry, BosonicTranslation, EigenNumMomentum, ParityConservation, EigenParityMomentum, SpinNumberConservation, EigenSpinNumMomentum},
         bit_fns::{bit_flip},
         number::NumberState,
         momentum::NumMomentumState,
+        parity::ParityMomentumState,
+        hubbard::{HubbardMomentumState, FermionCycleIterator},
+        ladder::{Ladder, LadderCycleIterator},
         iterator::{BitIterator, PairIterator, PeriodicPairIterator, PeriodicPairEnumerator, PeriodicDistancedPairIterator, PeriodicDistancedPairEnumerator, CycleIterator, CommenIterator},
     },
     bases::{
         BasisGenerator, Basis,
         number::BasisN,
         momentum::BasisNK,
+        parity::BasisPK,
+        hubbard::BasisHubbard,
+        ladder::BasisLadder,
+        embedding::SectorEmbedding,
     },
     hamiltonian::{
-        PeriodicIsing,PeriodicNearestXXZ, PeriodicNextNearestXXZ,
+        HamiltonianTerm, Hamiltonian, PeriodicIsing,PeriodicNearestXXZ, PeriodicNextNearestXXZ,
         degeneracy_pair, degeneracy_triple, prepare_energy_map, count_degeneracy_from,
-    }
+        sparse::{MatVec, SparseMatrix, sparse_sector_hamiltonian, sparse_number_sector_hamiltonian},
+        matrix::{SectorIndex, SectorMatrix, sector_hamiltonian, sector_hamiltonian_real, number_sector_hamiltonian, number_sector_hamiltonian_real},
+        disorder::{RandomField, RandomBondXXZ},
+        long_range::PeriodicLongRangeXXZ,
+        anisotropic::{PeriodicPairing, PeriodicXYZ, PeriodicTransverseIsing, TransverseField},
+        chiral::{PeriodicDM, PeriodicScalarChirality, PeriodicFluxXXZ},
+        hubbard::{PeriodicHubbard, hubbard_sector_hamiltonian},
+        ladder::{LadderXXZ, ladder_sector_hamiltonian},
+        lattice::{LatticeXXZ, lattice_sector_hamiltonian},
+    },
+    dynamics::{exact::ExactEvolution, krylov::KrylovEvolution, quench::{Quench, QuenchForm}, kicks::{KickSequence, Floquet}, ramp::{Ramp, RampIntegrator, RampWork, ramp_unitary, ramp_work}, work::{WorkDistribution, thermal_populations}, passivity::DiagonalState},
+    lattice::{Bond, Lattice},
+    spectrum::{
+        sector_spectra,
+        clustering::{Tolerance, EnergyCluster, cluster_levels, cluster_sectors},
+        degeneracy::{Multiplet, DegeneracyStructure},
+        classification::{SymmetryKind, SymmetrySet, DegeneracyOrigin, classify_multiplet, classify_degeneracy, accidental_multiplets},
+        statistics::{Ensemble, Unfolding, LevelStatistics, gap_ratios, mean_gap_ratio, unfolded_spacings, sector_statistics},
+        full::FullSpectrum,
+        form_factor::{SpectralFilter, SpectralFormFactor},
+        density::{Broadening, DensityOfStates},
+        thermodynamics::{ThermalSpectrum, ThermalState},
+        kpm::{KpmKernel, ChebyshevMoments, KernelPolynomial, spectral_bounds},
+    },
+    thermal::{Estimate, random_state, ftlm::{LanczosMethod, LanczosSample, LanczosThermalState, FiniteTemperatureLanczos}, tpq::{TPQPoint, MicrocanonicalTPQ, CanonicalTPQ}},
+    random::{rng_seed, gaussian},
+    spec::{ModelSpec, SectorSpec},
 };
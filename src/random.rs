@@ -0,0 +1,44 @@
+use rand::Rng;
+use rand_pcg::Pcg64;
+use std::f64::consts::PI;
+
+const INC : u128 = 0xa02bdbf7bb3c0a7ac28fa16a64abf96;
+
+pub fn rng_seed(seed : u128) -> Pcg64{
+    // Generator of PCG family with fixed stream, same as the one used in examples.
+    // seed : seed which determines the random sequence.
+
+    Pcg64::new(seed, INC)
+}
+
+pub fn gaussian<R : Rng + ?Sized>(rng : &mut R) -> f64{
+    // standard normal sample by the Box-Muller transform, 1 - u keeps the logarithm finite
+    let (u1, u2) : (f64, f64) = (rng.gen(), rng.gen());
+    (-2f64 * (1f64 - u1).ln()).sqrt() * (2f64 * PI * u2).cos()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_rng_seed(){
+        let mut rng1 = rng_seed(1234);
+        let mut rng2 = rng_seed(1234);
+        for _ in 0..10{
+            assert_eq!(rng1.gen::<u64>(), rng2.gen::<u64>());
+        }
+    }
+
+    #[test]
+    fn test_gaussian(){
+        let mut rng = rng_seed(1234);
+        let n = 100000;
+        let samples : Vec<f64> = (0..n).map(|_| gaussian(&mut rng)).collect();
+        let mean = samples.iter().sum::<f64>() / (n as f64);
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n as f64);
+        assert!(mean.abs() < 0.02);
+        assert!((variance - 1f64).abs() < 0.02);
+    }
+}
@@ -0,0 +1,536 @@
+use std::fs;
+use std::path::Path;
+use crate::prelude::*;
+
+// Declarative model specification in a small TOML-like format
+//
+//     [lattice]
+//     type = "chain"           # chain, ladder or custom
+//     length = 8
+//     periodic = true
+//     next_nearest = true      # adds bonds labeled "nnn" to a chain
+//
+//     [[term]]
+//     type = "xxz"             # xxz or field
+//     bond = "nn"
+//     delta_x = 1.0
+//     delta_z = 2.0
+//
+//     [sector]
+//     number = 4
+//     momentum = 0             # only with declared translation
+//
+// A custom lattice is given by `sites`, repeated `bond = [i, j, "label"]` and optional `translation = [...]`.
+// Lattices have at most MAX_SITES sites, and at most MAX_FULL_SITES when [sector] gives no number.
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum SpecValue{
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Array(Vec<SpecValue>),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpecTable{
+    pub name : String,
+    pub line : usize,
+    // (key, value, line), keys may repeat
+    pub entries : Vec<(String, SpecValue, usize)>,
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct SectorSpec{
+    pub number : Option<usize>,
+    pub momentum : Option<usize>,
+}
+
+#[derive(Clone)]
+pub struct ModelSpec{
+    pub lattice : Lattice,
+    pub hamiltonian : Hamiltonian,
+    pub sector : SectorSpec,
+}
+
+// bases enumerate all 2^L configurations, and without [sector] the dense matrix is 2^L x 2^L
+pub const MAX_SITES : usize = 24;
+pub const MAX_FULL_SITES : usize = 12;
+
+fn spec_error(line : usize, msg : String) -> Error{
+    Error::make_error_syntax(ErrorCode::InvalidSpec{ line, msg : msg.into_boxed_str() })
+}
+
+fn strip_comment(line : &str) -> &str{
+    let mut in_quote = false;
+    for (i, c) in line.char_indices(){
+        match c{
+            '"' => in_quote = !in_quote,
+            '#' if !in_quote => return &line[..i],
+            _ => {},
+        }
+    }
+    line
+}
+
+fn split_top_level(s : &str) -> Vec<&str>{
+    // split at commas which are not inside quotes or brackets
+    let mut result : Vec<&str> = Vec::new();
+    let (mut depth, mut in_quote, mut start) = (0i32, false, 0);
+    for (i, c) in s.char_indices(){
+        match c{
+            '"' => in_quote = !in_quote,
+            '[' if !in_quote => depth += 1,
+            ']' if !in_quote => depth -= 1,
+            ',' if !in_quote && depth == 0 => {
+                result.push(&s[start..i]);
+                start = i + 1;
+            },
+            _ => {},
+        }
+    }
+    result.push(&s[start..]);
+    result
+}
+
+pub fn parse_value(s : &str, line : usize) -> Result<SpecValue, Error>{
+    let s = s.trim();
+    if s.is_empty(){
+        return Err(spec_error(line, "missing value".to_string()));
+    }
+
+    if s.starts_with('"'){
+        if s.len() < 2 || !s.ends_with('"') || s[1..s.len() - 1].contains('"'){
+            return Err(spec_error(line, format!("unterminated string {}", s)));
+        }
+        return Ok(SpecValue::Str(s[1..s.len() - 1].to_string()));
+    }
+
+    if s.starts_with('['){
+        if !s.ends_with(']'){
+            return Err(spec_error(line, format!("unterminated array {}", s)));
+        }
+        let inner = s[1..s.len() - 1].trim();
+        if inner.is_empty(){
+            return Ok(SpecValue::Array(Vec::new()));
+        }
+        let values = split_top_level(inner).into_iter()
+                        .map(|x| parse_value(x, line))
+                        .collect::<Result<Vec<SpecValue>, Error>>()?;
+        return Ok(SpecValue::Array(values));
+    }
+
+    match s{
+        "true" => return Ok(SpecValue::Bool(true)),
+        "false" => return Ok(SpecValue::Bool(false)),
+        _ => {},
+    }
+
+    if let Ok(x) = s.parse::<i64>(){
+        return Ok(SpecValue::Int(x));
+    }
+    if let Ok(x) = s.parse::<f64>(){
+        return Ok(SpecValue::Float(x));
+    }
+    Err(spec_error(line, format!("invalid value {}", s)))
+}
+
+pub fn parse_tables(text : &str) -> Result<Vec<SpecTable>, Error>{
+    let mut tables : Vec<SpecTable> = Vec::new();
+
+    for (i, raw) in text.lines().enumerate(){
+        let line = i + 1;
+        let content = strip_comment(raw).trim();
+        if content.is_empty(){
+            continue;
+        }
+
+        if content.starts_with("[[") && content.ends_with("]]"){
+            // array of tables, every header starts a new table
+            let name = content[2..content.len() - 2].trim().to_string();
+            tables.push(SpecTable{ name, line, entries : Vec::new() });
+        } else if content.starts_with('[') && content.ends_with(']'){
+            let name = content[1..content.len() - 1].trim().to_string();
+            if tables.iter().any(|t| t.name == name){
+                return Err(spec_error(line, format!("duplicate section [{}]", name)));
+            }
+            tables.push(SpecTable{ name, line, entries : Vec::new() });
+        } else if let Some(pos) = content.find('='){
+            let key = content[..pos].trim();
+            if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'){
+                return Err(spec_error(line, format!("invalid key {}", key)));
+            }
+            let value = parse_value(&content[pos + 1..], line)?;
+            match tables.last_mut(){
+                Some(table) => table.entries.push((key.to_string(), value, line)),
+                None => return Err(spec_error(line, format!("key {} outside of any section", key))),
+            }
+        } else {
+            return Err(spec_error(line, format!("cannot parse {}", content)));
+        }
+    }
+
+    return Ok(tables);
+}
+
+impl SpecTable{
+    pub fn check_keys(&self, allowed : &[&str], repeated : &[&str]) -> Result<(), Error>{
+        for (idx, (key, _, line)) in self.entries.iter().enumerate(){
+            if !allowed.contains(&key.as_str()){
+                return Err(spec_error(*line, format!("unknown key {} in [{}]", key, self.name)));
+            }
+            if !repeated.contains(&key.as_str()) && self.entries[..idx].iter().any(|e| e.0 == *key){
+                return Err(spec_error(*line, format!("duplicate key {}", key)));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self, key : &str) -> Option<(&SpecValue, usize)>{
+        self.entries.iter().find(|e| e.0 == key).map(|e| (&e.1, e.2))
+    }
+
+    pub fn get_usize(&self, key : &str) -> Result<Option<usize>, Error>{
+        match self.get(key){
+            None => Ok(None),
+            Some((SpecValue::Int(x), _)) if *x >= 0 => Ok(Some(*x as usize)),
+            Some((_, line)) => Err(spec_error(line, format!("{} should be a non-negative integer", key))),
+        }
+    }
+
+    pub fn get_f64(&self, key : &str) -> Result<Option<f64>, Error>{
+        match self.get(key){
+            None => Ok(None),
+            Some((SpecValue::Int(x), _)) => Ok(Some(*x as f64)),
+            Some((SpecValue::Float(x), _)) => Ok(Some(*x)),
+            Some((_, line)) => Err(spec_error(line, format!("{} should be a number", key))),
+        }
+    }
+
+    pub fn get_bool(&self, key : &str) -> Result<Option<bool>, Error>{
+        match self.get(key){
+            None => Ok(None),
+            Some((SpecValue::Bool(x), _)) => Ok(Some(*x)),
+            Some((_, line)) => Err(spec_error(line, format!("{} should be true or false", key))),
+        }
+    }
+
+    pub fn get_str(&self, key : &str) -> Result<Option<&str>, Error>{
+        match self.get(key){
+            None => Ok(None),
+            Some((SpecValue::Str(x), _)) => Ok(Some(x.as_str())),
+            Some((_, line)) => Err(spec_error(line, format!("{} should be a string", key))),
+        }
+    }
+
+    pub fn require_usize(&self, key : &str) -> Result<usize, Error>{
+        self.get_usize(key)?.ok_or_else(|| spec_error(self.line, format!("missing key {} in [{}]", key, self.name)))
+    }
+}
+
+fn usize_array(value : &SpecValue, line : usize) -> Result<Vec<usize>, Error>{
+    match value{
+        SpecValue::Array(v) => v.iter().map(|x| match x{
+            SpecValue::Int(n) if *n >= 0 => Ok(*n as usize),
+            _ => Err(spec_error(line, "expected an array of non-negative integers".to_string())),
+        }).collect(),
+        _ => Err(spec_error(line, "expected an array of non-negative integers".to_string())),
+    }
+}
+
+fn check_sites(num_sites : Option<usize>, line : usize) -> Result<(), Error>{
+    match num_sites{
+        Some(n) if n <= MAX_SITES => Ok(()),
+        _ => Err(spec_error(line, format!("number of sites should be at most {}", MAX_SITES))),
+    }
+}
+
+fn build_lattice(table : &SpecTable) -> Result<Lattice, Error>{
+    let kind = table.get_str("type")?.unwrap_or("chain");
+    let periodic = table.get_bool("periodic")?.unwrap_or(true);
+    // errors of Lattice are reported at the header of [lattice]
+    let lattice_error = |e : Error| spec_error(table.line, format!("{}", e));
+
+    match kind{
+        "chain" => {
+            table.check_keys(&["type", "length", "periodic", "next_nearest"], &[])?;
+            let length = table.require_usize("length")?;
+            check_sites(Some(length), table.get("length").unwrap().1)?;
+            let mut lattice = Lattice::chain(length, periodic).map_err(lattice_error)?;
+            if table.get_bool("next_nearest")?.unwrap_or(false){
+                let num_bond = if periodic {length} else {length.max(2) - 2};
+                for i in 0..num_bond{
+                    lattice.add_bond(i, (i + 2) % length, "nnn").map_err(lattice_error)?;
+                }
+            }
+            Ok(lattice)
+        },
+        "ladder" => {
+            table.check_keys(&["type", "legs", "rungs", "periodic"], &[])?;
+            let (legs, rungs) = (table.require_usize("legs")?, table.require_usize("rungs")?);
+            check_sites(legs.checked_mul(rungs), table.get("rungs").unwrap().1)?;
+            let ladder = Ladder::new(legs, rungs);
+            let mut lattice = Lattice::ladder(&ladder).map_err(lattice_error)?;
+            if !periodic{
+                let rungs = ladder.rungs;
+                lattice.bonds.retain(|b| b.label != "leg" || b.i % rungs != rungs - 1);
+                lattice.translation = None;
+            }
+            Ok(lattice)
+        },
+        "custom" => {
+            table.check_keys(&["type", "sites", "bond", "translation"], &["bond"])?;
+            let sites = table.require_usize("sites")?;
+            check_sites(Some(sites), table.get("sites").unwrap().1)?;
+            let mut lattice = Lattice::new(sites);
+            for (key, value, line) in table.entries.iter(){
+                if key != "bond"{
+                    continue;
+                }
+                match value{
+                    SpecValue::Array(v) => match v.as_slice(){
+                        [SpecValue::Int(i), SpecValue::Int(j), SpecValue::Str(label)] if *i >= 0 && *j >= 0 => {
+                            lattice.add_bond(*i as usize, *j as usize, label).map_err(|e| spec_error(*line, format!("{}", e)))?;
+                        },
+                        _ => return Err(spec_error(*line, "bond should be [i, j, \"label\"]".to_string())),
+                    },
+                    _ => return Err(spec_error(*line, "bond should be [i, j, \"label\"]".to_string())),
+                }
+            }
+            if let Some((value, line)) = table.get("translation"){
+                lattice = lattice.with_translation(usize_array(value, line)?).map_err(|e| spec_error(line, format!("{}", e)))?;
+            }
+            Ok(lattice)
+        },
+        _ => Err(spec_error(table.get("type").map_or(table.line, |x| x.1), format!("unknown lattice type {}", kind))),
+    }
+}
+
+fn build_term(table : &SpecTable, lattice : &Lattice) -> Result<Box<dyn HamiltonianTerm>, Error>{
+    let kind = table.get_str("type")?.ok_or_else(|| spec_error(table.line, "missing key type in [[term]]".to_string()))?;
+
+    match kind{
+        "xxz" => {
+            table.check_keys(&["type", "bond", "delta_x", "delta_z"], &[])?;
+            let label = table.get_str("bond")?.unwrap_or("nn");
+            if !lattice.labels().contains(&label){
+                return Err(spec_error(table.get("bond").map_or(table.line, |x| x.1), format!("lattice has no bond labeled {}", label)));
+            }
+            let delta_x = table.get_f64("delta_x")?.unwrap_or(1f64);
+            let delta_z = table.get_f64("delta_z")?.unwrap_or(1f64);
+            Ok(Box::new(LatticeXXZ::new(lattice.clone()).with_coupling(label, delta_x, delta_z)))
+        },
+        "field" => {
+            // uniform field h * sum S^z_i
+            table.check_keys(&["type", "h"], &[])?;
+            let h = table.get_f64("h")?.ok_or_else(|| spec_error(table.line, "missing key h in [[term]]".to_string()))?;
+            Ok(Box::new(RandomField::new(vec![h; lattice.num_sites])))
+        },
+        _ => Err(spec_error(table.get("type").map_or(table.line, |x| x.1), format!("unknown term type {}", kind))),
+    }
+}
+
+impl ModelSpec{
+    pub fn parse(text : &str) -> Result<Self, Error>{
+        let tables = parse_tables(text)?;
+
+        for table in tables.iter(){
+            if !["lattice", "term", "sector"].contains(&table.name.as_str()){
+                return Err(spec_error(table.line, format!("unknown section [{}]", table.name)));
+            }
+        }
+
+        let (lattice, lattice_line) = match tables.iter().find(|t| t.name == "lattice"){
+            Some(table) => (build_lattice(table)?, table.line),
+            None => return Err(spec_error(0, "missing section [lattice]".to_string())),
+        };
+
+        let mut hamiltonian = Hamiltonian::new();
+        for table in tables.iter().filter(|t| t.name == "term"){
+            hamiltonian.terms.push(build_term(table, &lattice)?);
+        }
+        if hamiltonian.is_empty(){
+            return Err(spec_error(0, "no [[term]] is given".to_string()));
+        }
+
+        let mut sector = SectorSpec::default();
+        if let Some(table) = tables.iter().find(|t| t.name == "sector"){
+            table.check_keys(&["number", "momentum"], &[])?;
+            sector.number = table.get_usize("number")?;
+            sector.momentum = table.get_usize("momentum")?;
+
+            if let Some(m) = sector.number{
+                if m > lattice.num_sites{
+                    return Err(spec_error(table.get("number").unwrap().1, format!("number {} exceeds number of sites", m)));
+                }
+            }
+            if let Some(k) = sector.momentum{
+                let line = table.get("momentum").unwrap().1;
+                if !lattice.has_translation(){
+                    return Err(spec_error(line, "momentum needs a lattice with translation".to_string()));
+                }
+                if sector.number.is_none(){
+                    return Err(spec_error(line, "momentum needs number".to_string()));
+                }
+                if k >= lattice.translation_order(){
+                    return Err(spec_error(line, format!("momentum {} is out of range", k)));
+                }
+            }
+        }
+
+        if sector.number.is_none() && lattice.num_sites > MAX_FULL_SITES{
+            return Err(spec_error(lattice_line, format!("full Hilbert space needs at most {} sites, give number in [sector]", MAX_FULL_SITES)));
+        }
+
+        Ok(Self{
+            lattice,
+            hamiltonian,
+            sector,
+        })
+    }
+
+    pub fn from_file<P : AsRef<Path>>(path : P) -> Result<Self, Error>{
+        let text = fs::read_to_string(path).map_err(Error::make_error_io)?;
+        Self::parse(&text)
+    }
+
+    pub fn build_matrix(&self) -> Result<Array2<Complex64>, Error>{
+        // Hamiltonian in the declared sector, or in the full Hilbert space without [sector]
+        let length = self.lattice.num_sites;
+
+        match (self.sector.number, self.sector.momentum){
+            (Some(m), Some(k)) => {
+                let egn_v = EigenNumMomentum::new(m, k);
+                let (basis, indices) = self.lattice.build_light_nk(egn_v)?;
+                Ok(lattice_sector_hamiltonian(&self.lattice, &basis, &indices, egn_v, &self.hamiltonian))
+            },
+            (Some(m), None) => {
+                let (basis, indices) = self.lattice.build_light_n(EigenNumber::new(m))?;
+                Ok(number_sector_hamiltonian(&basis, &indices, &self.hamiltonian))
+            },
+            (None, _) => {
+                if length > MAX_FULL_SITES{
+                    return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+                }
+                let basis : Vec<(usize, usize)> = (0..(1 << length)).map(|n| (n, length)).collect();
+                let indices : FnvHashMap<usize, usize> = (0..(1 << length)).map(|n| (n, n)).collect();
+                Ok(number_sector_hamiltonian(&basis, &indices, &self.hamiltonian))
+            },
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use ndarray_linalg::close_l2;
+    use super::*;
+
+    const NEXT_NEAREST : &str = r#"
+# next nearest XXZ chain, same as interaction_info = 1 of examples
+[lattice]
+type = "chain"
+length = 6
+next_nearest = true
+
+[[term]]
+type = "xxz"
+bond = "nn"
+delta_x = 1.0
+delta_z = 2.0
+
+[[term]]
+type = "xxz"
+bond = "nnn"
+delta_x = 1
+delta_z = 0
+
+[sector]
+number = 3
+momentum = 1
+"#;
+
+    #[test]
+    fn test_parse_value(){
+        assert_eq!(parse_value("1", 1).unwrap(), SpecValue::Int(1));
+        assert_eq!(parse_value("-0.5", 1).unwrap(), SpecValue::Float(-0.5));
+        assert_eq!(parse_value(" \"nn\" ", 1).unwrap(), SpecValue::Str("nn".to_string()));
+        assert_eq!(parse_value("[0, 1, \"a,b\"]", 1).unwrap(),
+            SpecValue::Array(vec![SpecValue::Int(0), SpecValue::Int(1), SpecValue::Str("a,b".to_string())]));
+        assert!(parse_value("[0, 1", 1).is_err());
+        assert!(parse_value("abc", 1).is_err());
+    }
+
+    #[test]
+    fn test_model_spec(){
+        let spec = ModelSpec::parse(NEXT_NEAREST).unwrap();
+        assert_eq!(spec.sector, SectorSpec{ number : Some(3), momentum : Some(1) });
+        assert_eq!(spec.hamiltonian.len(), 2);
+
+        let egn_v = EigenNumMomentum::new(3, 1);
+        let (basis, indices) = BasisNK::new(egn_v, 6).build_light().unwrap();
+        let truth = sector_hamiltonian(&basis, &indices, egn_v, &PeriodicNextNearestXXZ::new(1.0, 1.0, 2.0));
+        close_l2(&spec.build_matrix().unwrap(), &truth, 1e-10);
+    }
+
+    #[test]
+    fn test_custom_spec(){
+        let text = r#"
+[lattice]
+type = "custom"
+sites = 3
+bond = [0, 1, "a"]
+bond = [1, 2, "a"]
+bond = [2, 0, "a"]
+translation = [2, 0, 1]
+
+[[term]]
+type = "xxz"
+bond = "a"
+delta_z = 2.0
+"#;
+        let spec = ModelSpec::parse(text).unwrap();
+        assert!(spec.lattice.has_translation());
+        let h = spec.build_matrix().unwrap();
+        assert_eq!(h.dim(), (8, 8));
+
+        let mut truth : Array2<Complex64> = Array2::zeros((8, 8));
+        let xxz = PeriodicNearestXXZ::new(1.0, 2.0);
+        for n in 0..8{
+            for (n2, value) in xxz.elements(n, 3){
+                truth[[n2, n]] += value;
+            }
+        }
+        close_l2(&h, &truth, 1e-10);
+    }
+
+    #[test]
+    fn test_spec_errors(){
+        let line_of = |text : &str| match ModelSpec::parse(text){
+            Err(e) => format!("{}", e),
+            Ok(_) => String::new(),
+        };
+
+        assert_eq!(line_of("[[term]]\ntype = \"xxz\"\n"), "Invalid specification at line 0 : missing section [lattice]");
+        assert_eq!(line_of("[lattice]\nlength = 4\nwidth = 2\n"), "Invalid specification at line 3 : unknown key width in [lattice]");
+        assert_eq!(line_of("[lattice]\nlength = 4\n[[term]]\ntype = \"xxz\"\nbond = \"rung\"\n"),
+            "Invalid specification at line 5 : lattice has no bond labeled rung");
+        assert_eq!(line_of("[lattice]\nlength = 4\nperiodic = false\n[[term]]\ntype = \"xxz\"\n[sector]\nnumber = 2\nmomentum = 1\n"),
+            "Invalid specification at line 8 : momentum needs a lattice with translation");
+        assert_eq!(line_of("[lattice]\nlength = four\n"), "Invalid specification at line 2 : invalid value four");
+        assert!(ModelSpec::parse("length = 4\n").err().unwrap().is_syntax());
+
+        // sizes are checked before any basis is enumerated
+        assert_eq!(line_of("[lattice]\nlength = 64\n[[term]]\ntype = \"xxz\"\n[sector]\nnumber = 2\n"),
+            "Invalid specification at line 2 : number of sites should be at most 24");
+        assert_eq!(line_of("[lattice]\ntype = \"ladder\"\nlegs = 2\nrungs = 13\n"),
+            "Invalid specification at line 4 : number of sites should be at most 24");
+        assert_eq!(line_of("[lattice]\nlength = 30\n[[term]]\ntype = \"xxz\"\n"),
+            "Invalid specification at line 2 : number of sites should be at most 24");
+        assert_eq!(line_of("[lattice]\nlength = 16\n[[term]]\ntype = \"xxz\"\n"),
+            "Invalid specification at line 1 : full Hilbert space needs at most 12 sites, give number in [sector]");
+        assert_eq!(line_of("[lattice]\nlength = 1\n[[term]]\ntype = \"xxz\"\n"),
+            "Invalid specification at line 1 : Invalid Argument input");
+        assert!(ModelSpec::parse("[lattice]\nlength = 16\n[[term]]\ntype = \"xxz\"\n[sector]\nnumber = 8\nmomentum = 0\n").is_ok());
+    }
+}
@@ -0,0 +1,150 @@
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SymmetryKind{
+    // (m, k) <-> (m, L - k)
+    Reflection,
+    // (m, k) <-> (L - m, k)
+    SpinFlip,
+    // (m, k) <-> (m ± 1, k)
+    SU2,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SymmetrySet{
+    pub reflection : bool,
+    pub spin_flip : bool,
+    pub su2 : bool,
+}
+
+impl SymmetrySet{
+    pub fn xxz(delta_x : f64, delta_z : f64) -> Self{
+        // PeriodicNearestXXZ is reflection and spin-flip symmetric, and SU(2) symmetric at delta_x = delta_z
+        Self{
+            reflection : true,
+            spin_flip : true,
+            su2 : (delta_x - delta_z).abs() < 1e-12,
+        }
+    }
+
+    pub fn relation(&self, a : EigenNumMomentum, b : EigenNumMomentum, length : usize) -> Option<SymmetryKind>{
+        let (ma, ka, mb, kb) = (a.total_number(), a.wave_number(), b.total_number(), b.wave_number());
+        if a == b{
+            return None;
+        }
+        if self.reflection && ma == mb && (ka + kb) % length == 0{
+            return Some(SymmetryKind::Reflection);
+        }
+        if self.spin_flip && ma + mb == length && ka == kb{
+            return Some(SymmetryKind::SpinFlip);
+        }
+        if self.su2 && ka == kb && (ma + 1 == mb || mb + 1 == ma){
+            return Some(SymmetryKind::SU2);
+        }
+        None
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DegeneracyOrigin{
+    NonDegenerate,
+    // symmetries connecting all members
+    Symmetry(Vec<SymmetryKind>),
+    Accidental,
+}
+
+fn find(parent : &mut Vec<usize>, i : usize) -> usize{
+    let mut root = i;
+    while parent[root] != root{
+        root = parent[root];
+    }
+    parent[i] = root;
+    root
+}
+
+pub fn classify_multiplet(multiplet : &Multiplet<EigenNumMomentum>, length : usize, symmetries : SymmetrySet) -> DegeneracyOrigin{
+    // Accidental when one sector appears twice or members are not connected by the symmetries
+    let members = &multiplet.members;
+    let n = members.len();
+    if n < 2{
+        return DegeneracyOrigin::NonDegenerate;
+    }
+    if (1..n).any(|i| members[..i].iter().any(|x| x.0 == members[i].0)){
+        return DegeneracyOrigin::Accidental;
+    }
+
+    let mut parent : Vec<usize> = (0..n).collect();
+    let mut kinds : Vec<SymmetryKind> = Vec::new();
+    for i in 0..n{
+        for j in (i + 1)..n{
+            if let Some(kind) = symmetries.relation(members[i].0, members[j].0, length){
+                let (ri, rj) = (find(&mut parent, i), find(&mut parent, j));
+                if ri != rj{
+                    parent[ri] = rj;
+                }
+                if !kinds.contains(&kind){
+                    kinds.push(kind);
+                }
+            }
+        }
+    }
+
+    let root = find(&mut parent, 0);
+    if (1..n).all(|i| find(&mut parent, i) == root){
+        kinds.sort();
+        DegeneracyOrigin::Symmetry(kinds)
+    } else {
+        DegeneracyOrigin::Accidental
+    }
+}
+
+pub fn classify_degeneracy(structure : &DegeneracyStructure<EigenNumMomentum>, length : usize, symmetries : SymmetrySet) -> Vec<DegeneracyOrigin>{
+    // in the order of structure.multiplets
+    structure.iter().map(|m| classify_multiplet(m, length, symmetries)).collect()
+}
+
+pub fn accidental_multiplets(structure : &DegeneracyStructure<EigenNumMomentum>, length : usize, symmetries : SymmetrySet) -> Vec<&Multiplet<EigenNumMomentum>>{
+    structure.iter().filter(|m| classify_multiplet(m, length, symmetries) == DegeneracyOrigin::Accidental).collect()
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn multiplet(members : Vec<(usize, usize)>) -> Multiplet<EigenNumMomentum>{
+        Multiplet{ energy : 0.0, spread : 0.0, members : members.into_iter().map(|(m, k)| (EigenNumMomentum::new(m, k), 0)).collect() }
+    }
+
+    #[test]
+    fn test_classify_multiplet(){
+        let length = 6;
+        let xxz = SymmetrySet::xxz(1.0, 2.0);
+        assert_eq!(classify_multiplet(&multiplet(vec![(2, 1)]), length, xxz), DegeneracyOrigin::NonDegenerate);
+        assert_eq!(classify_multiplet(&multiplet(vec![(2, 1), (2, 5)]), length, xxz), DegeneracyOrigin::Symmetry(vec![SymmetryKind::Reflection]));
+        assert_eq!(classify_multiplet(&multiplet(vec![(2, 1), (2, 5), (4, 1), (4, 5)]), length, xxz),
+            DegeneracyOrigin::Symmetry(vec![SymmetryKind::Reflection, SymmetryKind::SpinFlip]));
+        assert_eq!(classify_multiplet(&multiplet(vec![(2, 1), (2, 2)]), length, xxz), DegeneracyOrigin::Accidental);
+
+        let no_reflection = SymmetrySet{ reflection : false, ..xxz };
+        assert_eq!(classify_multiplet(&multiplet(vec![(2, 1), (2, 5)]), length, no_reflection), DegeneracyOrigin::Accidental);
+    }
+
+    #[test]
+    fn test_su2_multiplet(){
+        // ferromagnetic multiplet of the isotropic chain spans m = 0..L at k = 0
+        let length = 4;
+        let xxz = PeriodicNearestXXZ::new(1f64, 1f64);
+        let spectra = sector_spectra(length, &xxz).unwrap();
+        let structure = DegeneracyStructure::new(&spectra, Tolerance::Absolute(1e-8)).unwrap();
+        let symmetries = SymmetrySet::xxz(1.0, 1.0);
+
+        let ferro = structure.multiplet_of(EigenNumMomentum::new(0, 0), 0).unwrap();
+        assert_eq!(ferro.len(), length + 1);
+        match classify_multiplet(ferro, length, symmetries){
+            DegeneracyOrigin::Symmetry(kinds) => assert!(kinds.contains(&SymmetryKind::SU2)),
+            _ => panic!(),
+        }
+        assert_eq!(classify_degeneracy(&structure, length, symmetries).len(), structure.len());
+    }
+}
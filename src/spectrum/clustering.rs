@@ -0,0 +1,128 @@
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Tolerance{
+    Absolute(f64),
+    // relative to the larger magnitude of two neighbouring levels
+    Relative(f64),
+}
+
+impl Tolerance{
+    pub fn close(&self, a : f64, b : f64) -> bool{
+        match *self{
+            Tolerance::Absolute(tol) => (a - b).abs() <= tol,
+            Tolerance::Relative(tol) => (a - b).abs() <= tol * a.abs().max(b.abs()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EnergyCluster<V : EigenValue>{
+    pub energy : f64,
+    // largest minus smallest energy in the cluster
+    pub spread : f64,
+    // (sector, index in the sector) sorted by sector and index
+    pub members : Vec<(V, usize)>,
+}
+
+impl<V> EnergyCluster<V>
+    where V : EigenValue{
+    pub fn len(&self) -> usize{
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.members.is_empty()
+    }
+
+    pub fn is_degenerate(&self) -> bool{
+        self.members.len() > 1
+    }
+
+    pub fn contains(&self, egn_v : V, idx : usize) -> bool{
+        self.members.contains(&(egn_v, idx))
+    }
+}
+
+pub fn cluster_levels<V>(levels : &[(V, usize, f64)], tol : Tolerance) -> Vec<EnergyCluster<V>>
+    where V : EigenValue + Ord{
+    // Single linkage over sorted energies : neighbours closer than tol share a cluster,
+    // so the result does not depend on the order of levels
+
+    let mut sorted : Vec<(f64, V, usize)> = levels.iter().map(|&(v, i, e)| (e, v, i)).collect();
+    sorted.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap().then(a.1.cmp(&b.1)).then(a.2.cmp(&b.2)));
+
+    let mut clusters : Vec<EnergyCluster<V>> = Vec::new();
+    let mut start = 0;
+    for i in 0..sorted.len(){
+        let last = i + 1 == sorted.len();
+        if last || !tol.close(sorted[i].0, sorted[i + 1].0){
+            let group = &sorted[start..=i];
+            let mut members : Vec<(V, usize)> = group.iter().map(|x| (x.1, x.2)).collect();
+            members.sort();
+            clusters.push(EnergyCluster{
+                energy : group.iter().map(|x| x.0).sum::<f64>() / (group.len() as f64),
+                spread : group[group.len() - 1].0 - group[0].0,
+                members,
+            });
+            start = i + 1;
+        }
+    }
+
+    return clusters;
+}
+
+pub fn cluster_sectors<V>(spectra : &FnvHashMap<V, Array1<f64>>, tol : Tolerance) -> Vec<EnergyCluster<V>>
+    where V : EigenValue + Ord{
+    // Clustering across sectors, spectra as returned by eigvalsh of each sector
+    let levels : Vec<(V, usize, f64)> = spectra.iter()
+                    .flat_map(|(&v, energies)| energies.iter().enumerate().map(move |(i, &e)| (v, i, e)))
+                    .collect();
+    cluster_levels(&levels, tol)
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cluster_levels(){
+        let v = |k| EigenNumMomentum::new(2, k);
+        // chain of near levels is kept together, and input order does not matter
+        let levels = vec![(v(0), 0, 0.0), (v(1), 0, 0.6e-6), (v(2), 0, 1.2e-6), (v(0), 1, 1.0), (v(3), 0, 1.0 + 1e-9)];
+        let mut reversed = levels.clone();
+        reversed.reverse();
+
+        let clusters = cluster_levels(&levels, Tolerance::Absolute(1e-6));
+        assert_eq!(clusters, cluster_levels(&reversed, Tolerance::Absolute(1e-6)));
+        assert_eq!(clusters.len(), 2);
+        assert_eq!(clusters[0].members, vec![(v(0), 0), (v(1), 0), (v(2), 0)]);
+        assert!((clusters[0].spread - 1.2e-6).abs() < 1e-15);
+        assert!(clusters[1].contains(v(3), 0));
+
+        let clusters = cluster_levels(&levels, Tolerance::Relative(1e-8));
+        assert_eq!(clusters.len(), 4);
+        assert!(!clusters[0].is_degenerate());
+    }
+
+    #[test]
+    fn test_cluster_sectors(){
+        // k and L-k sectors of XXZ are degenerate
+        let length = 6;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+        let mut spectra = sector_spectra(length, &xxz).unwrap();
+        spectra.retain(|v, _| v.total_number() == 3);
+
+        let clusters = cluster_sectors(&spectra, Tolerance::Absolute(1e-8));
+        assert_eq!(clusters.iter().map(|c| c.len()).sum::<usize>(), 20);
+        for c in clusters.iter(){
+            for &(egn_v, _) in c.members.iter(){
+                let k = egn_v.wave_number();
+                if k != 0 && k != 3{
+                    assert!(c.members.iter().any(|x| x.0.wave_number() == length - k));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::hash::Hash;
+use crate::prelude::*;
+
+pub type Multiplet<V> = EnergyCluster<V>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DegeneracyStructure<V : EigenValue + Hash + Eq>{
+    pub multiplets : Vec<Multiplet<V>>,
+    // (sector, index) -> position of its multiplet
+    lookup : FnvHashMap<(V, usize), usize>,
+}
+
+impl<V> DegeneracyStructure<V>
+    where V : EigenValue + Hash + Eq + Ord{
+    pub fn from_multiplets(multiplets : Vec<Multiplet<V>>) -> Result<Self, Error>{
+        let mut lookup : FnvHashMap<(V, usize), usize> = FnvHashMap::default();
+        for (i, multiplet) in multiplets.iter().enumerate(){
+            for &member in multiplet.members.iter(){
+                if lookup.insert(member, i).is_some(){
+                    // a level can not belong to two multiplets
+                    return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+                }
+            }
+        }
+        Ok(Self{
+            multiplets,
+            lookup,
+        })
+    }
+
+    pub fn new(spectra : &FnvHashMap<V, Array1<f64>>, tol : Tolerance) -> Result<Self, Error>{
+        Self::from_multiplets(cluster_sectors(spectra, tol))
+    }
+
+    pub fn len(&self) -> usize{
+        self.multiplets.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.multiplets.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Multiplet<V>>{
+        self.multiplets.iter()
+    }
+
+    pub fn degenerate(&self) -> impl Iterator<Item = &Multiplet<V>>{
+        self.multiplets.iter().filter(|m| m.is_degenerate())
+    }
+
+    pub fn touching<'a>(&'a self, sector : V) -> impl Iterator<Item = &'a Multiplet<V>> + 'a{
+        // multiplets with at least one member in sector
+        self.multiplets.iter().filter(move |m| m.members.iter().any(|x| x.0 == sector))
+    }
+
+    pub fn multiplet_of(&self, sector : V, idx : usize) -> Option<&Multiplet<V>>{
+        self.lookup.get(&(sector, idx)).map(|&i| &self.multiplets[i])
+    }
+
+    pub fn degeneracy(&self, sector : V, idx : usize) -> usize{
+        self.multiplet_of(sector, idx).map_or(0, |m| m.len())
+    }
+
+    pub fn representative(&self, sector : V, idx : usize) -> Option<(V, usize)>{
+        // smallest member, members are sorted by sector and index
+        self.multiplet_of(sector, idx).map(|m| m.members[0])
+    }
+
+    pub fn representative_in(&self, sector : V, idx : usize, preferred : V) -> Option<(V, usize)>{
+        // first member in preferred sector if any, otherwise the representative
+        self.multiplet_of(sector, idx).map(|m| *m.members.iter().find(|x| x.0 == preferred).unwrap_or(&m.members[0]))
+    }
+
+    pub fn is_representative(&self, sector : V, idx : usize) -> bool{
+        self.representative(sector, idx) == Some((sector, idx))
+    }
+
+    pub fn weight(&self, sector : V, idx : usize) -> f64{
+        // 1 / degeneracy, 0 for unknown levels
+        match self.degeneracy(sector, idx){
+            0 => 0f64,
+            d => 1f64 / (d as f64),
+        }
+    }
+
+    pub fn multiplet_means(&self, values : &FnvHashMap<V, Array1<f64>>) -> Result<Vec<f64>, Error>{
+        // mean of values over members, in the order of multiplets
+        self.multiplets.iter().map(|m| {
+            let mut sum = 0f64;
+            for (sector, idx) in m.members.iter(){
+                match values.get(sector).and_then(|v| v.get(*idx)){
+                    Some(x) => sum += x,
+                    None => return Err(Error::make_error_syntax(ErrorCode::InvalidArgument)),
+                }
+            }
+            Ok(sum / (m.len() as f64))
+        }).collect()
+    }
+
+    pub fn average(&self, values : &FnvHashMap<V, Array1<f64>>) -> Result<FnvHashMap<V, Array1<f64>>, Error>{
+        // every value replaced by the mean over its multiplet, levels outside of the structure are kept
+        let means = self.multiplet_means(values)?;
+        let mut result = values.clone();
+        for (sector, array) in result.iter_mut(){
+            for (idx, x) in array.iter_mut().enumerate(){
+                if let Some(&i) = self.lookup.get(&(*sector, idx)){
+                    *x = means[i];
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_degeneracy_structure(){
+        let v = |k| EigenNumMomentum::new(2, k);
+        let mut spectra : FnvHashMap<EigenNumMomentum, Array1<f64>> = FnvHashMap::default();
+        spectra.insert(v(0), arr1(&[-1.0, 0.5]));
+        spectra.insert(v(1), arr1(&[0.5 + 1e-12, 2.0]));
+        spectra.insert(v(3), arr1(&[0.5, 2.0]));
+
+        let structure = DegeneracyStructure::new(&spectra, Tolerance::Absolute(1e-9)).unwrap();
+        assert_eq!(structure.len(), 3);
+        assert_eq!(structure.degenerate().count(), 2);
+        assert_eq!(structure.touching(v(0)).count(), 2);
+        assert_eq!(structure.degeneracy(v(1), 0), 3);
+        assert_eq!(structure.representative(v(3), 0), Some((v(0), 1)));
+        assert_eq!(structure.representative_in(v(0), 1, v(3)), Some((v(3), 0)));
+        assert!(structure.is_representative(v(1), 1));
+        assert!((structure.weight(v(3), 1) - 0.5).abs() < 1e-12);
+        assert_eq!(structure.weight(v(4), 0), 0f64);
+
+        let mut values : FnvHashMap<EigenNumMomentum, Array1<f64>> = FnvHashMap::default();
+        values.insert(v(0), arr1(&[1.0, 3.0]));
+        values.insert(v(1), arr1(&[6.0, 1.0]));
+        values.insert(v(3), arr1(&[0.0, 3.0]));
+        let averaged = structure.average(&values).unwrap();
+        assert_eq!(averaged.get(&v(0)), Some(&arr1(&[1.0, 3.0])));
+        assert_eq!(averaged.get(&v(1)), Some(&arr1(&[3.0, 2.0])));
+
+        values.remove(&v(3));
+        assert!(structure.average(&values).is_err());
+        assert!(DegeneracyStructure::from_multiplets(vec![
+            Multiplet{ energy : 0.0, spread : 0.0, members : vec![(v(0), 0)] },
+            Multiplet{ energy : 1.0, spread : 0.0, members : vec![(v(0), 0)] }]).is_err());
+    }
+}
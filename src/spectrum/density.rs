@@ -0,0 +1,97 @@
+use std::io::Write;
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Broadening{
+    // standard deviation
+    Gaussian(f64),
+    // half width at half maximum
+    Lorentzian(f64),
+}
+
+impl Broadening{
+    pub fn kernel(&self, x : f64) -> f64{
+        match *self{
+            Broadening::Gaussian(sigma) => (-x * x / (2f64 * sigma * sigma)).exp() / (sigma * (2f64 * PI).sqrt()),
+            Broadening::Lorentzian(gamma) => gamma / (PI * (x * x + gamma * gamma)),
+        }
+    }
+
+    fn width(&self) -> f64{
+        match *self{
+            Broadening::Gaussian(x) | Broadening::Lorentzian(x) => x,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DensityOfStates{
+    pub grid : Array1<f64>,
+    // states per unit energy, integrates to the dimension
+    pub density : Array1<f64>,
+}
+
+impl DensityOfStates{
+    pub fn new(spectrum : &FullSpectrum, grid : &[f64], broadening : Broadening) -> Result<Self, Error>{
+        if broadening.width() <= 0f64{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let density = grid.iter()
+            .map(|&x| spectrum.iter().map(|(e, g)| g as f64 * broadening.kernel(x - e)).sum())
+            .collect();
+        Ok(Self{
+            grid : grid.iter().cloned().collect(),
+            density,
+        })
+    }
+
+    pub fn uniform_grid(spectrum : &FullSpectrum, points : usize, broadening : Broadening) -> Result<Self, Error>{
+        // grid covering the spectrum with five widths of margin
+        if spectrum.is_empty() || points < 2{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let margin = 5f64 * broadening.width();
+        let grid = Array1::linspace(spectrum.energies[0] - margin, spectrum.energies[spectrum.len() - 1] + margin, points);
+        Self::new(spectrum, grid.as_slice().unwrap(), broadening)
+    }
+
+    pub fn integrated(&self) -> f64{
+        // trapezoidal rule over the grid
+        (1..self.grid.len()).map(|i| 0.5 * (self.grid[i] - self.grid[i - 1]) * (self.density[i] + self.density[i - 1])).sum()
+    }
+
+    pub fn normalized(&self) -> Array1<f64>{
+        let norm = self.integrated();
+        self.density.mapv(|x| x / norm)
+    }
+
+    pub fn write_table<W : Write>(&self, writer : &mut W) -> Result<(), Error>{
+        // energy and density
+        for (x, d) in self.grid.iter().zip(self.density.iter()){
+            writeln!(writer, "{:.05e}\t{:.05e}", x, d).map_err(Error::make_error_io)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_density_of_states(){
+        let spectrum = FullSpectrum::new(&[-1.0, 0.0, 2.0], &[2, 1, 3]).unwrap();
+        let dos = DensityOfStates::uniform_grid(&spectrum, 4001, Broadening::Gaussian(0.1)).unwrap();
+        assert!((dos.integrated() - 6.0).abs() < 1e-5);
+        assert!((dos.normalized().sum() * (dos.grid[1] - dos.grid[0]) - 1.0).abs() < 1e-3);
+
+        let peak = DensityOfStates::new(&spectrum, &[2.0], Broadening::Lorentzian(0.01)).unwrap();
+        assert!((peak.density[0] - 3.0 / (PI * 0.01)).abs() < 1e-1);
+        assert!(DensityOfStates::new(&spectrum, &[0.0], Broadening::Gaussian(0.0)).is_err());
+
+        let mut buffer : Vec<u8> = Vec::new();
+        peak.write_table(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 1);
+    }
+}
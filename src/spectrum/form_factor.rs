@@ -0,0 +1,119 @@
+use std::io::Write;
+use crate::prelude::*;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SpectralFilter{
+    Flat,
+    // exp(-(E - center)^2 / (2 width^2)), suppresses the spectral edges
+    Gaussian{ center : f64, width : f64 },
+}
+
+impl SpectralFilter{
+    pub fn weight(&self, energy : f64) -> f64{
+        match *self{
+            SpectralFilter::Flat => 1f64,
+            SpectralFilter::Gaussian{ center, width } => (-(energy - center).powi(2) / (2f64 * width * width)).exp(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct SpectralFormFactor{
+    pub times : Array1<f64>,
+    // <|Z(t)|^2> / <sum w^2>, with Z(t) = sum_n w_n exp(-i E_n t)
+    pub disconnected : Array1<f64>,
+    // (<|Z(t)|^2> - |<Z(t)>|^2) / <sum w^2>
+    pub connected : Array1<f64>,
+}
+
+impl SpectralFormFactor{
+    pub fn new(samples : &[FullSpectrum], times : &[f64], filter : SpectralFilter) -> Result<Self, Error>{
+        // Averages over samples, e.g. disorder realizations. Normalized so that the plateau of a
+        // spectrum without degeneracy is 1. Degenerate levels enter with their multiplicity.
+        if samples.is_empty() || samples.iter().any(|s| s.is_empty()){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        if let SpectralFilter::Gaussian{ width, .. } = filter{
+            if width <= 0f64{
+                return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+            }
+        }
+
+        let num = samples.len() as f64;
+        let weights : Vec<Vec<(f64, f64)>> = samples.iter()
+            .map(|s| s.iter().map(|(e, g)| (e, g as f64 * filter.weight(e))).collect())
+            .collect();
+        let norm = weights.iter().map(|w| w.iter().map(|x| x.1 * x.1).sum::<f64>()).sum::<f64>() / num;
+
+        let mut disconnected = Array1::<f64>::zeros(times.len());
+        let mut connected = Array1::<f64>::zeros(times.len());
+        for (i, &t) in times.iter().enumerate(){
+            let z : Vec<Complex64> = weights.iter()
+                .map(|w| w.iter().map(|&(e, x)| Complex64::new(0f64, -e * t).exp() * x).sum())
+                .collect();
+            let square = z.iter().map(|x| x.norm_sqr()).sum::<f64>() / num;
+            let mean = z.iter().sum::<Complex64>() / num;
+            disconnected[i] = square / norm;
+            connected[i] = (square - mean.norm_sqr()) / norm;
+        }
+
+        Ok(Self{
+            times : times.iter().cloned().collect(),
+            disconnected,
+            connected,
+        })
+    }
+
+    pub fn single(spectrum : &FullSpectrum, times : &[f64], filter : SpectralFilter) -> Result<Self, Error>{
+        // connected part vanishes for a single sample
+        Self::new(std::slice::from_ref(spectrum), times, filter)
+    }
+
+    pub fn write_table<W : Write>(&self, writer : &mut W) -> Result<(), Error>{
+        // time, disconnected and connected form factor
+        for i in 0..self.times.len(){
+            writeln!(writer, "{:.05e}\t{:.05e}\t{:.05e}", self.times[i], self.disconnected[i], self.connected[i]).map_err(Error::make_error_io)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_form_factor(){
+        let times = [0.0, 0.5, 1.0, PI];
+        let two = FullSpectrum::new(&[0.0, 1.0], &[1, 1]).unwrap();
+        let sff = SpectralFormFactor::single(&two, &times, SpectralFilter::Flat).unwrap();
+        for (i, &t) in times.iter().enumerate(){
+            assert!((sff.disconnected[i] - (1.0 + t.cos())).abs() < 1e-12);
+            assert!(sff.connected[i].abs() < 1e-12);
+        }
+
+        // degenerate levels count with multiplicity : (3 + e^{-it})(3 + e^{it}) / 10
+        let degenerate = FullSpectrum::new(&[0.0, 1.0], &[3, 1]).unwrap();
+        let sff = SpectralFormFactor::single(&degenerate, &times, SpectralFilter::Flat).unwrap();
+        assert!((sff.disconnected[0] - 1.6).abs() < 1e-12);
+        assert!((sff.disconnected[3] - 0.4).abs() < 1e-12);
+
+        // two samples with Z(pi) = 0 and 2
+        let shifted = FullSpectrum::new(&[0.0, 2.0], &[1, 1]).unwrap();
+        let sff = SpectralFormFactor::new(&[two.clone(), shifted], &times, SpectralFilter::Flat).unwrap();
+        assert!((sff.disconnected[3] - 1.0).abs() < 1e-12);
+        assert!((sff.connected[3] - 0.5).abs() < 1e-12);
+        assert!(sff.connected[0].abs() < 1e-12);
+
+        // filter only reweights levels
+        let sff = SpectralFormFactor::single(&two, &times, SpectralFilter::Gaussian{ center : 0.0, width : 1.0 }).unwrap();
+        let w = (-0.5f64).exp();
+        assert!((sff.disconnected[0] - (1.0 + w).powi(2) / (1.0 + w * w)).abs() < 1e-12);
+        assert!(SpectralFormFactor::single(&two, &times, SpectralFilter::Gaussian{ center : 0.0, width : 0.0 }).is_err());
+
+        let mut buffer : Vec<u8> = Vec::new();
+        sff.write_table(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), times.len());
+    }
+}
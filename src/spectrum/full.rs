@@ -0,0 +1,103 @@
+use std::hash::Hash;
+use std::io::Write;
+use crate::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FullSpectrum{
+    // sorted distinct levels
+    pub energies : Array1<f64>,
+    // number of states of the full Hilbert space at each level
+    pub degeneracies : Vec<usize>,
+}
+
+impl FullSpectrum{
+    pub fn new(energies : &[f64], degeneracies : &[usize]) -> Result<Self, Error>{
+        if energies.len() != degeneracies.len(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let mut levels : Vec<(f64, usize)> = energies.iter().cloned().zip(degeneracies.iter().cloned()).collect();
+        levels.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Ok(Self{
+            energies : levels.iter().map(|x| x.0).collect(),
+            degeneracies : levels.iter().map(|x| x.1).collect(),
+        })
+    }
+
+    pub fn from_sectors<V>(spectra : &FnvHashMap<V, Array1<f64>>) -> Self
+        where V : EigenValue{
+        // every level of every sector as a separate state
+        let energies : Vec<f64> = spectra.values().flat_map(|x| x.iter().cloned()).collect();
+        let degeneracies = vec![1; energies.len()];
+        Self::new(&energies, &degeneracies).unwrap()
+    }
+
+    pub fn from_structure<V>(structure : &DegeneracyStructure<V>) -> Self
+        where V : EigenValue + Hash + Eq + Ord{
+        // one level per multiplet, exact degeneracies merged
+        let energies : Vec<f64> = structure.iter().map(|m| m.energy).collect();
+        let degeneracies : Vec<usize> = structure.iter().map(|m| m.len()).collect();
+        Self::new(&energies, &degeneracies).unwrap()
+    }
+
+    pub fn len(&self) -> usize{
+        self.energies.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.energies.is_empty()
+    }
+
+    pub fn dim(&self) -> usize{
+        self.degeneracies.iter().sum()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (f64, usize)> + '_{
+        self.energies.iter().cloned().zip(self.degeneracies.iter().cloned())
+    }
+
+    pub fn mean_energy(&self) -> f64{
+        self.iter().map(|(e, g)| e * g as f64).sum::<f64>() / (self.dim() as f64)
+    }
+
+    pub fn energy_width(&self) -> f64{
+        // standard deviation over all states
+        let mean = self.mean_energy();
+        (self.iter().map(|(e, g)| (e - mean).powi(2) * g as f64).sum::<f64>() / (self.dim() as f64)).sqrt()
+    }
+
+    pub fn write_table<W : Write>(&self, writer : &mut W) -> Result<(), Error>{
+        // energy and degeneracy, one level per line
+        for (e, g) in self.iter(){
+            writeln!(writer, "{:.015e}\t{}", e, g).map_err(Error::make_error_io)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_full_spectrum(){
+        let length = 6;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+        let spectra = sector_spectra(length, &xxz).unwrap();
+        let full = FullSpectrum::from_sectors(&spectra);
+        let merged = FullSpectrum::from_structure(&DegeneracyStructure::new(&spectra, Tolerance::Absolute(1e-8)).unwrap());
+
+        assert_eq!(full.dim(), 1 << length);
+        assert_eq!(merged.dim(), 1 << length);
+        assert!(merged.len() < full.len());
+        assert!((full.mean_energy() - merged.mean_energy()).abs() < 1e-8);
+        // trace of XXZ vanishes
+        assert!(full.mean_energy().abs() < 1e-10);
+
+        let mut buffer : Vec<u8> = Vec::new();
+        merged.write_table(&mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), merged.len());
+
+        assert!(FullSpectrum::new(&[0.0, 1.0], &[1]).is_err());
+    }
+}
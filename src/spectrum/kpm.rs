@@ -0,0 +1,264 @@
+use rand::Rng;
+use crate::prelude::*;
+use crate::dynamics::krylov::{lanczos, tridiagonal_eigh};
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum KpmKernel{
+    // resolution about pi / N, positive reconstruction
+    Jackson,
+    // with lambda, suited for Green functions
+    Lorentz(f64),
+    // plain truncation, shows Gibbs oscillations
+    Dirichlet,
+}
+
+impl KpmKernel{
+    pub fn coefficients(&self, num_moments : usize) -> Vec<f64>{
+        let n = num_moments as f64;
+        (0..num_moments).map(|i| {
+            let i = i as f64;
+            match *self{
+                KpmKernel::Jackson => {
+                    let q = PI / (n + 1f64);
+                    ((n - i + 1f64) * (q * i).cos() + (q * i).sin() / q.tan()) / (n + 1f64)
+                },
+                KpmKernel::Lorentz(lambda) => (lambda * (1f64 - i / n)).sinh() / lambda.sinh(),
+                KpmKernel::Dirichlet => 1f64,
+            }
+        }).collect()
+    }
+}
+
+pub fn spectral_bounds<M, R>(op : &M, steps : usize, rng : &mut R) -> Result<(f64, f64), Error>
+    where M : MatVec + ?Sized, R : Rng{
+    // extreme Ritz values of a Lanczos run, widened by their residuals and one percent of the width
+    if op.dim() == 0{
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+    let start = random_state(op.dim(), rng);
+    let (_, alpha, beta) = lanczos(op, &start, steps.min(op.dim()).max(1));
+    let (theta, vecs) = tridiagonal_eigh(&alpha, &beta)?;
+    let s = theta.len();
+    let residual = |j : usize| beta[s - 1] * vecs[[s - 1, j]].abs();
+    let margin = 0.01 * (theta[s - 1] - theta[0]).max(1e-8);
+    Ok((theta[0] - residual(0) - margin, theta[s - 1] + residual(s - 1) + margin))
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ChebyshevMoments{
+    // mu_n = <T_n(H~)>, with H~ = (H - b) / a
+    pub moments : Vec<f64>,
+    pub a : f64,
+    pub b : f64,
+}
+
+impl ChebyshevMoments{
+    pub fn new<M>(op : &M, bounds : (f64, f64), phi : &Array1<Complex64>, num_moments : usize) -> Result<Self, Error>
+        where M : MatVec + ?Sized{
+        // <phi|T_n(H~)|phi> by the Chebyshev recursion, phi need not be normalized
+        let (low, high) = bounds;
+        if high <= low || num_moments < 2 || phi.len() != op.dim(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let a = (high - low) / 2f64;
+        let b = (high + low) / 2f64;
+        let scaled = |v : &Array1<Complex64>| (op.matvec(v) - v * Complex64::from(b)) / Complex64::from(a);
+        let overlap = |v : &Array1<Complex64>| phi.iter().zip(v.iter()).map(|(x, y)| x.conj() * y).sum::<Complex64>().re;
+
+        let mut moments = Vec::with_capacity(num_moments);
+        let mut prev = phi.clone();
+        let mut now = scaled(phi);
+        moments.push(overlap(&prev));
+        moments.push(overlap(&now));
+        for _ in 2..num_moments{
+            let next = scaled(&now) * Complex64::from(2f64) - &prev;
+            moments.push(overlap(&next));
+            prev = now;
+            now = next;
+        }
+
+        Ok(Self{
+            moments,
+            a,
+            b,
+        })
+    }
+
+    pub fn stochastic<M, R>(op : &M, bounds : (f64, f64), num_moments : usize, num_random : usize, rng : &mut R) -> Result<Self, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // Tr T_n(H~) estimated by D times the mean over normalized random vectors
+        if num_random == 0{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let dim = op.dim() as f64;
+        let mut result = Self::new(op, bounds, &random_state(op.dim(), rng), num_moments)?;
+        for _ in 1..num_random{
+            let sample = Self::new(op, bounds, &random_state(op.dim(), rng), num_moments)?;
+            result.moments.iter_mut().zip(sample.moments.iter()).for_each(|(x, y)| *x += y);
+        }
+        result.moments.iter_mut().for_each(|x| *x *= dim / (num_random as f64));
+        return Ok(result);
+    }
+
+    pub fn len(&self) -> usize{
+        self.moments.len()
+    }
+
+    pub fn is_empty(&self) -> bool{
+        self.moments.is_empty()
+    }
+
+    pub fn add(&mut self, other : &ChebyshevMoments) -> Result<(), Error>{
+        // sum of spectra, e.g. of several sectors with common bounds
+        if self.len() != other.len() || (self.a - other.a).abs() > 1e-12 || (self.b - other.b).abs() > 1e-12{
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        self.moments.iter_mut().zip(other.moments.iter()).for_each(|(x, y)| *x += y);
+        Ok(())
+    }
+
+    pub fn chebyshev_grid(&self, points : usize) -> Vec<f64>{
+        // energies of the Chebyshev nodes x_k = cos(pi (k + 1/2) / points), ascending
+        (0..points).rev().map(|k| self.a * (PI * (k as f64 + 0.5) / (points as f64)).cos() + self.b).collect()
+    }
+
+    pub fn density(&self, kernel : KpmKernel, grid : &[f64]) -> Array1<f64>{
+        // (g_0 mu_0 + 2 sum g_n mu_n T_n(x)) / (pi a sqrt(1 - x^2)), zero outside of the bounds
+        let g = kernel.coefficients(self.len());
+        grid.iter().map(|&e| {
+            let x = (e - self.b) / self.a;
+            if x <= -1f64 || x >= 1f64{
+                return 0f64;
+            }
+            let theta = x.acos();
+            let sum = g[0] * self.moments[0] + 2f64 * (1..self.len()).map(|n| g[n] * self.moments[n] * (n as f64 * theta).cos()).sum::<f64>();
+            sum / (PI * self.a * (1f64 - x * x).sqrt())
+        }).collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct KernelPolynomial{
+    pub num_moments : usize,
+    pub num_random : usize,
+    pub kernel : KpmKernel,
+    // Lanczos steps for the spectral bounds
+    pub bound_steps : usize,
+}
+
+impl KernelPolynomial{
+    pub fn new(num_moments : usize, num_random : usize, kernel : KpmKernel) -> Self{
+        Self{
+            num_moments,
+            num_random,
+            kernel,
+            bound_steps : 50,
+        }
+    }
+
+    pub fn density_of_states<M, R>(&self, op : &M, grid : &[f64], rng : &mut R) -> Result<DensityOfStates, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // normalized to the dimension of op
+        let bounds = spectral_bounds(op, self.bound_steps, rng)?;
+        let moments = ChebyshevMoments::stochastic(op, bounds, self.num_moments, self.num_random, rng)?;
+        Ok(DensityOfStates{
+            grid : grid.iter().cloned().collect(),
+            density : moments.density(self.kernel, grid),
+        })
+    }
+
+    pub fn momentum_sector_dos<H, R>(&self, egn_v : EigenNumMomentum, length : usize, hamiltonian : &H, grid : &[f64], rng : &mut R) -> Result<DensityOfStates, Error>
+        where H : HamiltonianTerm + ?Sized, R : Rng{
+        // sparse BasisNK sector, never stored as a dense matrix
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light()?;
+        let h = sparse_sector_hamiltonian(&basis, &indices, egn_v, hamiltonian);
+        self.density_of_states(&h, grid, rng)
+    }
+
+    pub fn local_density<M, R>(&self, op : &M, phi : &Array1<Complex64>, grid : &[f64], rng : &mut R) -> Result<Array1<f64>, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // sum_n |<n|phi>|^2 delta(E - E_n)
+        let bounds = spectral_bounds(op, self.bound_steps, rng)?;
+        Ok(ChebyshevMoments::new(op, bounds, phi, self.num_moments)?.density(self.kernel, grid))
+    }
+
+    pub fn correlation<M, R>(&self, op : &M, a_psi : &Array1<Complex64>, e0 : f64, omega : &[f64], rng : &mut R) -> Result<Array1<f64>, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // S(omega) = sum_n |<n|A|psi>|^2 delta(omega - (E_n - e0)), for A|psi> and the energy e0 of |psi>
+        let grid : Vec<f64> = omega.iter().map(|w| w + e0).collect();
+        self.local_density(op, a_psi, &grid, rng)
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hamiltonian::sparse::xxz_test_sector;
+    use super::*;
+
+    #[test]
+    fn test_kernels(){
+        for kernel in [KpmKernel::Jackson, KpmKernel::Lorentz(4f64), KpmKernel::Dirichlet].iter(){
+            let g = kernel.coefficients(64);
+            assert!((g[0] - 1f64).abs() < 1e-12);
+            assert!(g.windows(2).all(|w| w[1] <= w[0] + 1e-12));
+        }
+        assert!(KpmKernel::Jackson.coefficients(64)[63].abs() < 1e-2);
+    }
+
+    #[test]
+    fn test_spectral_bounds(){
+        let (h, eval, _) = xxz_test_sector(EigenNumMomentum::new(5, 1), 10);
+        let (low, high) = spectral_bounds(&h, 50, &mut rng_seed(5)).unwrap();
+        let width = eval[eval.len() - 1] - eval[0];
+        assert!(low <= eval[0] && high >= eval[eval.len() - 1]);
+        assert!(eval[0] - low < 0.1 * width && high - eval[eval.len() - 1] < 0.1 * width);
+    }
+
+    #[test]
+    fn test_chebyshev_moments(){
+        let (h, eval, evec) = xxz_test_sector(EigenNumMomentum::new(5, 1), 10);
+        let bounds = spectral_bounds(&h, 50, &mut rng_seed(5)).unwrap();
+        let mut phi : Array1<Complex64> = Array1::zeros(h.dim());
+        phi[0] = Complex64::from(1f64);
+        phi[1] = Complex64::new(0f64, 0.5f64);
+
+        // exact moments from the eigen decomposition
+        let moments = ChebyshevMoments::new(&h, bounds, &phi, 40).unwrap();
+        let evec_h : Array2<Complex64> = conjugate(&evec);
+        let weights = evec_h.dot(&phi).mapv(|x| x.norm_sqr());
+        for (n, mu) in moments.moments.iter().enumerate(){
+            let exact : f64 = eval.iter().zip(weights.iter())
+                            .map(|(&e, &w)| w * (n as f64 * ((e - moments.b) / moments.a).acos()).cos())
+                            .sum();
+            assert!((mu - exact).abs() < 1e-8);
+        }
+
+        // Gauss-Chebyshev quadrature integrates the reconstruction to mu_0
+        let points = 128;
+        let grid = moments.chebyshev_grid(points);
+        let density = moments.density(KpmKernel::Jackson, &grid);
+        let integral : f64 = grid.iter().zip(density.iter())
+                        .map(|(&e, &rho)| {
+                            let x = (e - moments.b) / moments.a;
+                            rho * PI * moments.a * (1f64 - x * x).sqrt() / (points as f64)
+                        }).sum();
+        assert!((integral - 1.25).abs() < 1e-10);
+
+        let mut total = moments.clone();
+        total.add(&moments).unwrap();
+        assert!((total.moments[3] - 2f64 * moments.moments[3]).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_kpm_dos(){
+        let (h, eval, _) = xxz_test_sector(EigenNumMomentum::new(5, 1), 10);
+        let kpm = KernelPolynomial::new(64, 10, KpmKernel::Jackson);
+        let grid : Vec<f64> = Array1::linspace(eval[0] - 1f64, eval[eval.len() - 1] + 1f64, 2001).to_vec();
+        let dos = kpm.momentum_sector_dos(EigenNumMomentum::new(5, 1), 10, &PeriodicNearestXXZ::new(1f64, 0.5f64), &grid, &mut rng_seed(9)).unwrap();
+        // random vectors are normalized, so the total weight is exact up to the quadrature
+        assert!((dos.integrated() / (h.dim() as f64) - 1f64).abs() < 0.05);
+        assert!(dos.density.iter().all(|&x| x > -1e-8));
+        assert!(kpm.local_density(&h, &Array1::zeros(3), &grid, &mut rng_seed(9)).is_err());
+    }
+}
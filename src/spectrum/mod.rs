@@ -0,0 +1,25 @@
+use crate::prelude::*;
+
+pub mod clustering;
+pub mod degeneracy;
+pub mod classification;
+pub mod statistics;
+pub mod full;
+pub mod form_factor;
+pub mod density;
+pub mod thermodynamics;
+pub mod kpm;
+
+pub fn sector_spectra<H>(length : usize, hamiltonian : &H) -> Result<FnvHashMap<EigenNumMomentum, Array1<f64>>, Error>
+    where H : HamiltonianTerm + ?Sized{
+    // Energies of every (number, momentum) sector of the periodic chain, the input of the functions in this module
+    let (basis_map, indices) = Basis::new(length).build_light_nk();
+
+    let mut spectra : FnvHashMap<EigenNumMomentum, Array1<f64>> = FnvHashMap::default();
+    for (&egn_v, basis) in basis_map.iter(){
+        let h = SectorMatrix::build(basis, &indices, egn_v, hamiltonian);
+        spectra.insert(egn_v, h.eigvalsh()?);
+    }
+
+    return Ok(spectra);
+}
@@ -0,0 +1,288 @@
+use crate::prelude::*;
+use ndarray_linalg::Solve;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Ensemble{
+    Poisson,
+    GOE,
+    GUE,
+}
+
+fn erf(x : f64) -> f64{
+    // Abramowitz and Stegun 7.1.26, absolute error below 1.5e-7
+    let t = 1.0 / (1.0 + 0.3275911 * x.abs());
+    let poly = t * (0.254829592 + t * (-0.284496736 + t * (1.421413741 + t * (-1.453152027 + t * 1.061405429))));
+    let y = 1.0 - poly * (-x * x).exp();
+    if x < 0.0 { -y } else { y }
+}
+
+impl Ensemble{
+    pub fn mean_gap_ratio(&self) -> f64{
+        // large matrix limits of <min(s_n, s_n+1) / max(s_n, s_n+1)>
+        match self{
+            Ensemble::Poisson => 2.0 * 2f64.ln() - 1.0,
+            Ensemble::GOE => 0.5307,
+            Ensemble::GUE => 0.5996,
+        }
+    }
+
+    pub fn spacing_density(&self, s : f64) -> f64{
+        // Wigner surmise for unit mean spacing
+        match self{
+            Ensemble::Poisson => (-s).exp(),
+            Ensemble::GOE => PI / 2.0 * s * (-PI * s * s / 4.0).exp(),
+            Ensemble::GUE => 32.0 / (PI * PI) * s * s * (-4.0 * s * s / PI).exp(),
+        }
+    }
+
+    pub fn spacing_cdf(&self, s : f64) -> f64{
+        match self{
+            Ensemble::Poisson => 1.0 - (-s).exp(),
+            Ensemble::GOE => 1.0 - (-PI * s * s / 4.0).exp(),
+            Ensemble::GUE => erf(2.0 * s / PI.sqrt()) - 4.0 * s / PI * (-4.0 * s * s / PI).exp(),
+        }
+    }
+
+    pub fn ratio_density(&self, r : f64) -> f64{
+        // density of the ratio restricted to [0, 1], from the 3x3 surmise of Atas et al.
+        let (beta, z) = match self{
+            Ensemble::Poisson => return 2.0 / ((1.0 + r) * (1.0 + r)),
+            Ensemble::GOE => (1.0, 8.0 / 27.0),
+            Ensemble::GUE => (2.0, 4.0 * PI / (81.0 * 3f64.sqrt())),
+        };
+        2.0 * (r + r * r).powf(beta) / (z * (1.0 + r + r * r).powf(1.0 + 1.5 * beta))
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Unfolding{
+    // least squares polynomial fit of the staircase function, with the degree
+    Polynomial(usize),
+    // mean level density over the given number of neighbours on each side
+    Local(usize),
+}
+
+fn sorted(energies : &[f64]) -> Vec<f64>{
+    let mut sorted = energies.to_vec();
+    sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    sorted
+}
+
+pub fn gap_ratios(energies : &[f64]) -> Vec<f64>{
+    // min(s_n, s_n+1) / max(s_n, s_n+1), skipping pairs of exactly degenerate gaps
+    let energies = sorted(energies);
+    let gaps : Vec<f64> = energies.windows(2).map(|w| w[1] - w[0]).collect();
+    gaps.windows(2)
+        .filter(|w| w[0].max(w[1]) > 0.0)
+        .map(|w| w[0].min(w[1]) / w[0].max(w[1]))
+        .collect()
+}
+
+pub fn mean_gap_ratio(energies : &[f64]) -> Option<f64>{
+    let ratios = gap_ratios(energies);
+    if ratios.is_empty(){
+        return None;
+    }
+    Some(ratios.iter().sum::<f64>() / (ratios.len() as f64))
+}
+
+pub fn unfolded_spacings(energies : &[f64], unfolding : Unfolding) -> Result<Vec<f64>, Error>{
+    // Spacings of the unfolded spectrum, normalized to unit mean
+    let energies = sorted(energies);
+    let n = energies.len();
+    if n < 2{
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+
+    let mut spacings : Vec<f64> = match unfolding{
+        Unfolding::Polynomial(degree) => {
+            if degree + 1 > n{
+                return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+            }
+            // rescale to [-1, 1] to keep the normal equations well conditioned
+            let (lo, hi) = (energies[0], energies[n - 1]);
+            if hi <= lo{
+                return Err(Error::make_error_msg("Spectrum is fully degenerate".to_string()));
+            }
+            let x : Vec<f64> = energies.iter().map(|&e| 2.0 * (e - lo) / (hi - lo) - 1.0).collect();
+            let powers = |x : f64| (0..=degree).map(|p| x.powi(p as i32)).collect::<Vec<f64>>();
+
+            let mut a = Array2::<f64>::zeros((degree + 1, degree + 1));
+            let mut b = Array1::<f64>::zeros(degree + 1);
+            for (i, &xi) in x.iter().enumerate(){
+                let p = powers(xi);
+                let staircase = i as f64 + 0.5;
+                for r in 0..=degree{
+                    b[r] += p[r] * staircase;
+                    for c in 0..=degree{
+                        a[[r, c]] += p[r] * p[c];
+                    }
+                }
+            }
+            let coeff = a.solve_into(b).map_err(|e| Error::make_error_msg(e.to_string()))?;
+            let unfolded : Vec<f64> = x.iter().map(|&xi| powers(xi).iter().zip(coeff.iter()).map(|(p, c)| p * c).sum()).collect();
+            unfolded.windows(2).map(|w| w[1] - w[0]).collect()
+        },
+        Unfolding::Local(window) => {
+            let window = window.max(1);
+            let mut spacings = Vec::with_capacity(n - 1);
+            for i in 0..(n - 1){
+                let lo = i.saturating_sub(window);
+                let hi = (i + 1 + window).min(n - 1);
+                let width = energies[hi] - energies[lo];
+                if width <= 0.0{
+                    return Err(Error::make_error_msg("Spectrum is degenerate over an unfolding window".to_string()));
+                }
+                spacings.push((energies[i + 1] - energies[i]) * ((hi - lo) as f64) / width);
+            }
+            spacings
+        },
+    };
+
+    let mean = spacings.iter().sum::<f64>() / (spacings.len() as f64);
+    if mean <= 0.0{
+        return Err(Error::make_error_msg("Unfolded spectrum has no positive spacing".to_string()));
+    }
+    spacings.iter_mut().for_each(|s| *s /= mean);
+    return Ok(spacings);
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LevelStatistics{
+    pub num_levels : usize,
+    pub mean_ratio : f64,
+    // unfolded spacings with unit mean, in order of energy
+    pub spacings : Vec<f64>,
+}
+
+impl LevelStatistics{
+    pub fn new(energies : &Array1<f64>, unfolding : Unfolding) -> Result<Self, Error>{
+        let energies = energies.to_vec();
+        let mean_ratio = mean_gap_ratio(&energies).ok_or_else(|| Error::make_error_syntax(ErrorCode::InvalidArgument))?;
+        Ok(Self{
+            num_levels : energies.len(),
+            mean_ratio,
+            spacings : unfolded_spacings(&energies, unfolding)?,
+        })
+    }
+
+    pub fn ks_distance(&self, ensemble : Ensemble) -> f64{
+        // Kolmogorov-Smirnov distance between the unfolded spacings and the surmise
+        let mut spacings = self.spacings.clone();
+        spacings.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let n = spacings.len() as f64;
+        spacings.iter().enumerate().map(|(i, &s)| {
+            let cdf = ensemble.spacing_cdf(s);
+            (cdf - (i as f64) / n).abs().max(((i + 1) as f64 / n - cdf).abs())
+        }).fold(0.0, f64::max)
+    }
+
+    pub fn closest(&self) -> Ensemble{
+        // ensemble whose reference <r> is nearest
+        let mut best = Ensemble::Poisson;
+        for &e in [Ensemble::GOE, Ensemble::GUE].iter(){
+            if (self.mean_ratio - e.mean_gap_ratio()).abs() < (self.mean_ratio - best.mean_gap_ratio()).abs(){
+                best = e;
+            }
+        }
+        best
+    }
+}
+
+pub fn sector_statistics<V>(spectra : &FnvHashMap<V, Array1<f64>>, unfolding : Unfolding) -> Result<FnvHashMap<V, LevelStatistics>, Error>
+    where V : EigenValue{
+    // Statistics of each sector separately, since mixing sectors hides level repulsion.
+    // Sectors with less than three levels are skipped.
+    let mut result = FnvHashMap::default();
+    for (&egn_v, energies) in spectra.iter(){
+        if energies.len() < 3{
+            continue;
+        }
+        result.insert(egn_v, LevelStatistics::new(energies, unfolding)?);
+    }
+    return Ok(result);
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rand::Rng;
+    use ndarray_linalg::EigValsh;
+
+    #[test]
+    fn test_references(){
+        // surmise densities are normalized and have unit mean spacing
+        for &e in [Ensemble::Poisson, Ensemble::GOE, Ensemble::GUE].iter(){
+            let ds = 1e-3;
+            let (norm, mean) = (0..20000).map(|i| (i as f64 + 0.5) * ds)
+                .fold((0.0, 0.0), |(n, m), s| (n + e.spacing_density(s) * ds, m + s * e.spacing_density(s) * ds));
+            assert!((norm - 1.0).abs() < 1e-6);
+            assert!((mean - 1.0).abs() < 1e-6);
+            assert!((e.spacing_cdf(3.0) - (0..3000).map(|i| e.spacing_density((i as f64 + 0.5) * ds) * ds).sum::<f64>()).abs() < 1e-6);
+
+            let ratio_norm = (0..1000).map(|i| e.ratio_density((i as f64 + 0.5) * 1e-3) * 1e-3).sum::<f64>();
+            assert!((ratio_norm - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_gap_ratios(){
+        assert_eq!(gap_ratios(&[0.0, 1.0, 3.0, 4.0]), vec![0.5, 0.5]);
+        assert_eq!(mean_gap_ratio(&[3.0, 0.0, 1.0, 2.0]), Some(1.0));
+        assert_eq!(mean_gap_ratio(&[0.0, 1.0]), None);
+
+        let spacings = unfolded_spacings(&[0.0, 1.0, 2.0, 3.0, 4.0], Unfolding::Local(2)).unwrap();
+        assert!(spacings.iter().all(|s| (s - 1.0).abs() < 1e-12));
+        // staircase of sqrt(i) levels is exactly quadratic
+        let energies : Vec<f64> = (0..6).map(|i| (i as f64).sqrt()).collect();
+        let spacings = unfolded_spacings(&energies, Unfolding::Polynomial(2)).unwrap();
+        assert!(spacings.iter().all(|s| (s - 1.0).abs() < 1e-8));
+    }
+
+    #[test]
+    fn test_ensembles(){
+        let mut rng = rng_seed(2718);
+        let n = 800;
+
+        let poisson : Array1<f64> = (0..4 * n).map(|_| rng.gen::<f64>()).collect();
+        let stat = LevelStatistics::new(&poisson, Unfolding::Local(10)).unwrap();
+        assert_eq!(stat.closest(), Ensemble::Poisson);
+        assert!(stat.ks_distance(Ensemble::Poisson) < stat.ks_distance(Ensemble::GOE));
+
+        let mut goe = Array2::<f64>::zeros((n, n));
+        for i in 0..n{
+            for j in 0..=i{
+                let x = gaussian(&mut rng);
+                goe[[i, j]] = x;
+                goe[[j, i]] = x;
+            }
+        }
+        let stat = LevelStatistics::new(&goe.eigvalsh(UPLO::Lower).unwrap(), Unfolding::Polynomial(9)).unwrap();
+        assert_eq!(stat.closest(), Ensemble::GOE);
+
+        let mut gue = Array2::<Complex64>::zeros((n, n));
+        for i in 0..n{
+            gue[[i, i]] = Complex64::new(gaussian(&mut rng), 0.0);
+            for j in 0..i{
+                let x = Complex64::new(gaussian(&mut rng), gaussian(&mut rng));
+                gue[[i, j]] = x;
+                gue[[j, i]] = x.conj();
+            }
+        }
+        let stat = LevelStatistics::new(&gue.eigvalsh(UPLO::Lower).unwrap(), Unfolding::Local(10)).unwrap();
+        assert_eq!(stat.closest(), Ensemble::GUE);
+    }
+
+    #[test]
+    fn test_sector_statistics(){
+        let length = 8;
+        let xxz = PeriodicNearestXXZ::new(1f64, 0.5f64);
+        let spectra = sector_spectra(length, &xxz).unwrap();
+        let stats = sector_statistics(&spectra, Unfolding::Local(3)).unwrap();
+        assert!(!stats.contains_key(&EigenNumMomentum::new(0, 0)));
+        let stat = stats.get(&EigenNumMomentum::new(4, 1)).unwrap();
+        assert_eq!(stat.num_levels, spectra.get(&EigenNumMomentum::new(4, 1)).unwrap().len());
+        assert_eq!(stat.spacings.len(), stat.num_levels - 1);
+    }
+}
@@ -0,0 +1,209 @@
+use std::io::Write;
+use crate::prelude::*;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ThermalSpectrum{
+    pub length : usize,
+    pub energies : Vec<f64>,
+    // S^z = m - L/2 of the sector of each level
+    pub magnetizations : Vec<f64>,
+    // number of states represented by each level
+    pub weights : Vec<f64>,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ThermalState{
+    pub beta : f64,
+    pub log_partition : f64,
+    pub energy : f64,
+    pub specific_heat : f64,
+    pub entropy : f64,
+    // uniform susceptibility per site
+    pub susceptibility : f64,
+}
+
+impl ThermalSpectrum{
+    pub fn new(length : usize, energies : Vec<f64>, magnetizations : Vec<f64>, weights : Vec<f64>) -> Result<Self, Error>{
+        if energies.is_empty() || energies.len() != magnetizations.len() || energies.len() != weights.len()
+            || weights.iter().any(|&w| w < 0f64){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Ok(Self{
+            length,
+            energies,
+            magnetizations,
+            weights,
+        })
+    }
+
+    pub fn from_sectors_with<V, F>(spectra : &FnvHashMap<V, Array1<f64>>, length : usize, multiplicity : F) -> Result<Self, Error>
+        where V : NumberConservation, F : Fn(V) -> usize{
+        // multiplicity gives the number of equivalent sectors a stored sector stands for
+        let (mut energies, mut magnetizations, mut weights) = (Vec::new(), Vec::new(), Vec::new());
+        for (&egn_v, spectrum) in spectra.iter(){
+            let m = egn_v.total_number() as f64 - (length as f64) / 2f64;
+            let w = multiplicity(egn_v) as f64;
+            for &e in spectrum.iter(){
+                energies.push(e);
+                magnetizations.push(m);
+                weights.push(w);
+            }
+        }
+        Self::new(length, energies, magnetizations, weights)
+    }
+
+    pub fn from_sectors<V>(spectra : &FnvHashMap<V, Array1<f64>>, length : usize) -> Result<Self, Error>
+        where V : NumberConservation{
+        // every sector stored, each level is one state
+        Self::from_sectors_with(spectra, length, |_| 1)
+    }
+
+    pub fn from_reflected_sectors<V>(spectra : &FnvHashMap<V, Array1<f64>>, length : usize) -> Result<Self, Error>
+        where V : NumberConservation + TranslationalSymmetry{
+        // only 0 <= k <= L/2 stored, k and L - k sectors have the same spectrum under reflection
+        if spectra.keys().any(|v| 2 * v.wave_number() > length){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        Self::from_sectors_with(spectra, length, |v| if v.wave_number() == 0 || 2 * v.wave_number() == length { 1 } else { 2 })
+    }
+
+    pub fn dim(&self) -> f64{
+        self.weights.iter().sum()
+    }
+
+    fn boltzmann(&self, beta : f64) -> (f64, f64, Vec<f64>){
+        // ln of the shifted sum, the shift min(beta E) and populations (log-sum-exp)
+        let shift = self.energies.iter().map(|&e| beta * e).fold(f64::INFINITY, f64::min);
+        let factors : Vec<f64> = self.energies.iter().zip(self.weights.iter())
+                        .map(|(&e, &w)| w * (-(beta * e) + shift).exp())
+                        .collect();
+        let sum : f64 = factors.iter().sum();
+        (sum.ln(), shift, factors.iter().map(|x| x / sum).collect())
+    }
+
+    fn average<F>(populations : &[f64], values : &[f64], f : F) -> f64
+        where F : Fn(f64) -> f64{
+        populations.iter().zip(values.iter()).map(|(&p, &x)| p * f(x)).sum()
+    }
+
+    pub fn log_partition(&self, beta : f64) -> f64{
+        let (log_sum, shift, _) = self.boltzmann(beta);
+        log_sum - shift
+    }
+
+    pub fn free_energy(&self, beta : f64) -> f64{
+        -self.log_partition(beta) / beta
+    }
+
+    pub fn populations(&self, beta : f64) -> Vec<f64>{
+        // probability of each level, degeneracy included
+        self.boltzmann(beta).2
+    }
+
+    pub fn at(&self, beta : f64) -> ThermalState{
+        // beta >= 0 and finite, energies are measured from the ground energy to avoid cancellation
+        let (log_sum, shift, p) = self.boltzmann(beta);
+        let e0 = self.energies.iter().cloned().fold(f64::INFINITY, f64::min);
+        let excess = Self::average(&p, &self.energies, |e| e - e0);
+        let excess_var = Self::average(&p, &self.energies, |e| (e - e0 - excess).powi(2));
+        let mag = Self::average(&p, &self.magnetizations, |m| m);
+        let mag_var = Self::average(&p, &self.magnetizations, |m| (m - mag).powi(2));
+
+        ThermalState{
+            beta,
+            log_partition : log_sum - shift,
+            energy : e0 + excess,
+            specific_heat : beta * beta * excess_var,
+            // ln Z + beta <E>, where shift = beta E0 cancels
+            entropy : log_sum + beta * excess,
+            susceptibility : beta * mag_var / (self.length as f64),
+        }
+    }
+
+    pub fn scan(&self, betas : &[f64]) -> Vec<ThermalState>{
+        betas.iter().map(|&b| self.at(b)).collect()
+    }
+
+    pub fn write_table<W : Write>(&self, betas : &[f64], writer : &mut W) -> Result<(), Error>{
+        // temperature, energy, specific heat, entropy and susceptibility per site
+        let l = self.length as f64;
+        for s in self.scan(betas){
+            writeln!(writer, "{:.05e}\t{:.05e}\t{:.05e}\t{:.05e}\t{:.05e}", 1f64 / s.beta, s.energy / l, s.specific_heat / l, s.entropy / l, s.susceptibility)
+                .map_err(Error::make_error_io)?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_two_level(){
+        let spec = ThermalSpectrum::new(1, vec![0.0, 1.0], vec![-0.5, 0.5], vec![1.0, 2.0]).unwrap();
+        assert_eq!(spec.dim(), 3.0);
+
+        let beta = 0.7f64;
+        let z = 1.0 + 2.0 * (-beta).exp();
+        let p1 = 2.0 * (-beta).exp() / z;
+        let s = spec.at(beta);
+        assert!((s.log_partition - z.ln()).abs() < 1e-12);
+        assert!((s.energy - p1).abs() < 1e-12);
+        assert!((s.specific_heat - beta * beta * p1 * (1.0 - p1)).abs() < 1e-12);
+        assert!((s.entropy - (z.ln() + beta * p1)).abs() < 1e-12);
+        assert!((s.susceptibility - beta * p1 * (1.0 - p1)).abs() < 1e-12);
+        assert!((spec.free_energy(beta) + z.ln() / beta).abs() < 1e-12);
+
+        // infinite and very low temperature
+        assert!((spec.at(0.0).entropy - 3f64.ln()).abs() < 1e-12);
+        let cold = spec.at(1e4);
+        assert!(cold.log_partition.is_finite() && cold.entropy.abs() < 1e-12 && cold.specific_heat.abs() < 1e-12);
+        assert!(cold.log_partition.abs() < 1e-12);
+
+        assert!(ThermalSpectrum::new(1, vec![0.0], vec![], vec![1.0]).is_err());
+    }
+
+    #[test]
+    fn test_xxz_thermodynamics(){
+        let length = 6;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+
+        let spectra = sector_spectra(length, &xxz).unwrap();
+        let full = ThermalSpectrum::from_sectors(&spectra, length).unwrap();
+        assert_eq!(full.dim(), 64.0);
+
+        let half : FnvHashMap<EigenNumMomentum, Array1<f64>> = spectra.iter()
+                        .filter(|(v, _)| 2 * v.wave_number() <= length)
+                        .map(|(&v, e)| (v, e.clone()))
+                        .collect();
+        let reflected = ThermalSpectrum::from_reflected_sectors(&half, length).unwrap();
+        assert!(ThermalSpectrum::from_reflected_sectors(&spectra, length).is_err());
+
+        let mut number_spectra : FnvHashMap<EigenNumber, Array1<f64>> = FnvHashMap::default();
+        for m in 0..=length{
+            let (basis, indices) = BasisN::new(EigenNumber(m), length).build_light().unwrap();
+            let h = SectorMatrix::build_number(&basis, &indices, &xxz);
+            number_spectra.insert(EigenNumber(m), h.eigvalsh().unwrap());
+        }
+        let number = ThermalSpectrum::from_sectors(&number_spectra, length).unwrap();
+
+        for &beta in [0.1, 1.0, 10.0].iter(){
+            let s = full.at(beta);
+            for other in [reflected.at(beta), number.at(beta)].iter(){
+                assert!((s.log_partition - other.log_partition).abs() < 1e-10);
+                assert!((s.specific_heat - other.specific_heat).abs() < 1e-8);
+                assert!((s.susceptibility - other.susceptibility).abs() < 1e-8);
+            }
+        }
+
+        // free spins at high temperature : chi = beta / 4
+        let beta = 1e-6;
+        assert!((full.at(beta).susceptibility / beta - 0.25).abs() < 1e-5);
+
+        let mut buffer : Vec<u8> = Vec::new();
+        full.write_table(&[0.5, 1.0], &mut buffer).unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap().lines().count(), 2);
+    }
+}
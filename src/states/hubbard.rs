@@ -0,0 +1,205 @@
+use crate::prelude::*;
+use super::bit_fns::sum_bit;
+
+// Configuration of spinful fermions on `length` sites is stored as up + (down << length).
+// Creation operators are ordered with all up orbitals (site 0 .. length) before down orbitals.
+
+pub fn split_species(num : usize, length : usize) -> (usize, usize){
+    (num % (1 << length), num >> length)
+}
+
+pub fn join_species(up : usize, down : usize, length : usize) -> usize{
+    up + (down << length)
+}
+
+pub fn fermion_cyclic_move(num : usize, length : usize) -> (usize, f64){
+    // Same direction with cyclic_move for both species.
+    // Particle at site 0 goes to the end of its species block, passing the other n - 1 particles.
+    let (up, down) = split_species(num, length);
+    let mut sign = 1f64;
+    for &x in &[up, down]{
+        if x % 2 == 1 && sum_bit(x) % 2 == 0{
+            sign = -sign;
+        }
+    }
+
+    (join_species(cyclic_move_unsafe(up, length), cyclic_move_unsafe(down, length), length), sign)
+}
+
+pub fn fermion_period(num : usize, length : usize) -> (usize, f64){
+    // Number of distinct configurations in the orbit, and the sign of T^period |num>
+    let mut temp = num;
+    let mut sign = 1f64;
+    let mut count = 0;
+    loop{
+        let (next, s) = fermion_cyclic_move(temp, length);
+        temp = next;
+        sign *= s;
+        count += 1;
+        if temp == num{
+            return (count, sign);
+        }
+    }
+}
+
+pub fn fermion_translation_sign(num : usize, step : usize, length : usize) -> f64{
+    // sign of T^step |num>
+    let mut temp = num;
+    let mut sign = 1f64;
+    for _ in 0..step{
+        let (next, s) = fermion_cyclic_move(temp, length);
+        temp = next;
+        sign *= s;
+    }
+    return sign;
+}
+
+pub fn is_fermion_rep(num : usize, length : usize) -> bool{
+    let mut m = num;
+    loop{
+        m = fermion_cyclic_move(m, length).0;
+        if m == num{
+            break;
+        } else if m < num {
+            return false;
+        }
+    }
+    return true;
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+pub struct FermionCycleIterator{
+    start : usize,
+    num : usize,
+    sign : f64,
+    idx : usize,
+    length : usize,
+}
+
+impl FermionCycleIterator{
+    pub fn new(num : usize, length : usize) -> Result<Self, Error>{
+        if num >= (1 << (2 * length)) {
+            return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+        }
+        Ok(Self{
+            start : num,
+            num,
+            sign : 1f64,
+            idx : 0,
+            length,
+        })
+    }
+}
+
+impl Iterator for FermionCycleIterator{
+    type Item = (usize, f64);
+
+    fn next(&mut self) -> Option<Self::Item>{
+        // (T^idx |start> up to sign, the sign)
+        if self.idx > 0 && self.num == self.start{
+            return None;
+        }
+
+        let result = (self.num, self.sign);
+        let (next, s) = fermion_cyclic_move(self.num, self.length);
+        self.num = next;
+        self.sign *= s;
+        self.idx += 1;
+        return Some(result);
+    }
+}
+
+// =====================================================================================================
+// =====================================================================================================
+
+pub type HubbardMomentumState = EigenState<EigenSpinNumMomentum>;
+
+impl HubbardMomentumState{
+    pub fn new(num : usize, length : usize, eigen_v : &EigenSpinNumMomentum) -> Option<Self>{
+        // Return state only if num is representative configuration of eigen_v
+
+        let (up, down) = split_species(num, length);
+        if sum_bit(up) != eigen_v.up_number() || sum_bit(down) != eigen_v.down_number()
+            || !is_fermion_rep(num, length){
+            return None;
+        }
+
+        let (period, sign) = fermion_period(num, length);
+        if !eigen_v.check_fermion_commensurability(period, sign, length){
+            return None;
+        }
+
+        let omega = eigen_v.phase_factor(length).inv();
+        let mut coeff = Complex64::from((period as f64).sqrt() / (length as f64));
+        let mut result = HubbardMomentumState{
+            state : FnvHashMap::<usize, (usize, Complex64)>::default(),
+            index : Representation(*eigen_v, num),
+            length,
+        };
+
+        for (idx, (n, s)) in FermionCycleIterator::new(num, length).unwrap().enumerate(){
+            result.state.insert(n, (idx, coeff * s));
+            coeff *= omega;
+        }
+
+        return Some(result);
+    }
+
+    pub fn up_number(&self) -> usize{
+        self.index.get_eigenvalue().up_number()
+    }
+
+    pub fn down_number(&self) -> usize{
+        self.index.get_eigenvalue().down_number()
+    }
+
+    pub fn wave_number(&self) -> usize{
+        self.index.get_eigenvalue().wave_number()
+    }
+
+    pub fn normalize_factor(&self) -> Complex64{
+        let p = fermion_period(self.rep(), self.length).0;
+        Complex64::from((p as f64).sqrt() / (self.length as f64))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_fermion_cyclic_move(){
+        let length = 3;
+        // one up fermion hopping around : no sign
+        assert_eq!(fermion_cyclic_move(join_species(1, 0, length), length), (join_species(4, 0, length), 1f64));
+        // two up fermions, one passes the other
+        assert_eq!(fermion_cyclic_move(join_species(3, 0, length), length), (join_species(5, 0, length), -1f64));
+        assert_eq!(fermion_cyclic_move(join_species(6, 0, length), length), (join_species(3, 0, length), 1f64));
+        // species are moved independently
+        assert_eq!(fermion_cyclic_move(join_species(3, 3, length), length), (join_species(5, 5, length), 1f64));
+
+        assert_eq!(fermion_period(join_species(3, 0, length), length), (3, 1f64));
+        // full translation never gives sign, but half of it can
+        assert_eq!(fermion_period(join_species(3, 0, 4), 4), (4, 1f64));
+        assert_eq!(fermion_period(join_species(5, 0, 4), 4), (2, -1f64));
+        assert_eq!(fermion_translation_sign(join_species(3, 0, length), 1, length), -1f64);
+        assert_eq!(fermion_translation_sign(join_species(3, 0, length), 2, length), 1f64);
+    }
+
+    #[test]
+    fn test_hubbard_state(){
+        let length = 4;
+        // T^2 |0101> = -|0101>, so only odd k survive
+        let num = join_species(5, 0, length);
+        assert_eq!(HubbardMomentumState::new(num, length, &EigenSpinNumMomentum::new(2, 0, 0)), None);
+        assert_eq!(HubbardMomentumState::new(num, length, &EigenSpinNumMomentum::new(2, 0, 2)), None);
+        let state = HubbardMomentumState::new(num, length, &EigenSpinNumMomentum::new(2, 0, 1)).unwrap();
+        assert_eq!(state.where_is(join_species(10, 0, length)), Some(1));
+        assert_eq!(state.where_is(join_species(3, 0, length)), None);
+
+        assert!(HubbardMomentumState::new(join_species(3, 0, length), length, &EigenSpinNumMomentum::new(2, 0, 0)).is_some());
+        assert!(HubbardMomentumState::new(join_species(1, 1, length), length, &EigenSpinNumMomentum::new(1, 1, 0)).is_some());
+        assert_eq!(HubbardMomentumState::new(join_species(1, 2, length), length, &EigenSpinNumMomentum::new(1, 1, 0)), None);
+    }
+}
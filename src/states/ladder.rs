@@ -0,0 +1,135 @@
+use crate::prelude::*;
+
+// Site x of leg w is stored at bit w * rungs + x.
+// Translation moves every leg by cyclic_move at once, leg exchange maps leg w to leg (legs - 1 - w).
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct Ladder{
+    pub legs : usize,
+    pub rungs : usize,
+}
+
+impl Ladder{
+    pub fn new(legs : usize, rungs : usize) -> Self{
+        Self{
+            legs,
+            rungs,
+        }
+    }
+
+    pub fn num_sites(&self) -> usize{
+        self.legs * self.rungs
+    }
+
+    pub fn site(&self, leg : usize, x : usize) -> usize{
+        leg * self.rungs + x
+    }
+
+    pub fn leg_of(&self, num : usize, leg : usize) -> usize{
+        (num >> (leg * self.rungs)) % (1 << self.rungs)
+    }
+
+    pub fn translate(&self, num : usize) -> usize{
+        let mut result = 0;
+        for w in 0..self.legs{
+            result += cyclic_move_unsafe(self.leg_of(num, w), self.rungs) << (w * self.rungs);
+        }
+        return result;
+    }
+
+    pub fn leg_exchange(&self, num : usize) -> usize{
+        let mut result = 0;
+        for w in 0..self.legs{
+            result += self.leg_of(num, w) << ((self.legs - 1 - w) * self.rungs);
+        }
+        return result;
+    }
+
+    pub fn cycle_iter(&self, num : usize) -> LadderCycleIterator{
+        match LadderCycleIterator::new(*self, num){
+            Ok(x) => x,
+            Err(e) => panic!("{}", e),
+        }
+    }
+
+    pub fn period(&self, num : usize) -> usize{
+        self.cycle_iter(num).count()
+    }
+
+    pub fn is_rep(&self, num : usize) -> bool{
+        // minimal over leg-translations
+        self.cycle_iter(num).all(|n| n >= num)
+    }
+
+    pub fn orbit(&self, num : usize, exchange : bool) -> Vec<(usize, usize, usize)>{
+        // (T^d P^s num, d, s) for every group element, s = 0 only if leg exchange is not used
+        let mut result : Vec<(usize, usize, usize)> = Vec::new();
+        let exchanged = [num, self.leg_exchange(num)];
+        let n_s = if exchange {2} else {1};
+
+        for s in 0..n_s{
+            let mut temp = exchanged[s];
+            for d in 0..self.rungs{
+                result.push((temp, d, s));
+                temp = self.translate(temp);
+            }
+        }
+        return result;
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct LadderCycleIterator{
+    ladder : Ladder,
+    start : usize,
+    num : usize,
+    idx : usize,
+}
+
+impl LadderCycleIterator{
+    pub fn new(ladder : Ladder, num : usize) -> Result<Self, Error>{
+        if num >= (1 << ladder.num_sites()) {
+            return Err(Error::make_error_syntax(ErrorCode::OverFlow));
+        }
+        Ok(Self{
+            ladder,
+            start : num,
+            num,
+            idx : 0,
+        })
+    }
+}
+
+impl Iterator for LadderCycleIterator{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item>{
+        if self.idx > 0 && (self.num == self.start || self.idx == self.ladder.rungs){
+            return None;
+        }
+
+        let result = self.num;
+        self.num = self.ladder.translate(self.num);
+        self.idx += 1;
+        return Some(result);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ladder_moves(){
+        let ladder = Ladder::new(2, 3);
+        // leg 0 : 001, leg 1 : 010
+        let num = 1 + (2 << 3);
+        assert_eq!(ladder.translate(num), 4 + (1 << 3));
+        assert_eq!(ladder.leg_exchange(num), 2 + (1 << 3));
+        assert_eq!(ladder.cycle_iter(num).collect::<Vec<usize>>(), vec![num, 4 + (1 << 3), 2 + (4 << 3)]);
+        assert_eq!(ladder.period(7 + (7 << 3)), 1);
+        assert!(ladder.is_rep(num));
+        assert!(!ladder.is_rep(4 + (1 << 3)));
+    }
+}
@@ -9,6 +9,9 @@ pub mod bit_fns;
 pub mod iterator;
 pub mod number;
 pub mod momentum;
+pub mod parity;
+pub mod hubbard;
+pub mod ladder;
 pub mod symmetry;
 pub mod representation;
 
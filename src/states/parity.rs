@@ -0,0 +1,78 @@
+use crate::prelude::*;
+
+use super::representation::FindRepresentation;
+
+pub type ParityMomentumState = EigenState<EigenParityMomentum>;
+
+impl ParityMomentumState{
+    pub fn new<T>(s : &dyn State<T>, eigen_v : &EigenParityMomentum) -> Option<Self>
+        where T : EigenValue + LowerThan<EigenParityMomentum>{
+        // Return state only if s is representative state of eigen_v
+
+        if !eigen_v.is_rep(s){
+            return None;
+        };
+
+        return Some(Self::new_unsafe(s, eigen_v));
+    }
+
+    pub fn new_unsafe<T>(s : &dyn State<T>, eigen_v : &EigenParityMomentum) -> Self
+        where T : EigenValue + LowerThan<EigenParityMomentum>{
+        // Use when s is representative state of EigenParityMomentum in certain.
+
+        let period = s.period();
+        let length = s.length();
+        let rep = s.rep();
+
+        let omega = eigen_v.phase_factor(length).inv();
+        let mut coeff = Complex64::from((period as f64).sqrt() / (length as f64));
+        let mut result = ParityMomentumState{
+            state : FnvHashMap::<usize, (usize, Complex64)>::default(),
+            index : Representation(*eigen_v, rep),
+            length,
+        };
+
+        for (idx, n) in (rep, length).cycle_iter().enumerate(){
+            result.state.insert(n, (idx, coeff));
+            coeff *= omega;
+        }
+
+        return result;
+    }
+
+    pub fn parity(&self) -> usize{
+        self.index.get_eigenvalue().parity()
+    }
+
+    pub fn wave_number(&self) -> usize{
+        self.index.get_eigenvalue().wave_number()
+    }
+
+    pub fn phase_factor(&self) -> Complex64{
+        self.index.get_eigenvalue().phase_factor(self.length())
+    }
+
+    pub fn normalize_factor(&self) -> Complex64{
+        let p = period_unsafe(self.rep(), self.length);
+        Complex64::from((p as f64).sqrt() / (self.length as f64))
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_state(){
+        let pkstate = ParityMomentumState::new(&SimpleState::new(5, 4), &EigenParityMomentum::new(0, 0)).unwrap();
+        assert_eq!(pkstate.where_is(5), Some(0));
+        assert_eq!(pkstate.where_is(10), Some(1));
+        assert_eq!(pkstate.parity(), 0);
+
+        assert_eq!(ParityMomentumState::new(&SimpleState::new(5, 4), &EigenParityMomentum::new(1, 0)), None);
+        assert_eq!(ParityMomentumState::new(&SimpleState::new(10, 4), &EigenParityMomentum::new(0, 0)), None);
+        assert_eq!(ParityMomentumState::new(&SimpleState::new(5, 4), &EigenParityMomentum::new(0, 1)), None);
+        assert!(ParityMomentumState::new(&SimpleState::new(7, 4), &EigenParityMomentum::new(1, 1)).is_some());
+    }
+}
@@ -1,4 +1,5 @@
 use crate::prelude::*;
+use super::hubbard::{split_species, fermion_period, FermionCycleIterator};
 
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Hash)]
@@ -6,6 +7,8 @@ pub struct Representation<T : EigenValue>(pub T, pub usize);
 
 pub type RepNum = Representation<EigenNumber>;
 pub type RepNumMomentum = Representation<EigenNumMomentum>;
+pub type RepParityMomentum = Representation<EigenParityMomentum>;
+pub type RepSpinNumMomentum = Representation<EigenSpinNumMomentum>;
 
 impl<T> Representation<T>
     where T : EigenValue{
@@ -94,6 +97,77 @@ impl<T> FindRepresentation<T> for EigenNumMomentum
     }
 }
 
+impl<T> FindRepresentation<T> for EigenParityMomentum
+    where T : EigenValue + LowerThan<EigenParityMomentum>{
+    fn find_rep(&self, state : &dyn State<T>) -> Option<usize> {
+        if state.bit_sum() % 2 != self.parity(){
+            return None;
+        }
+
+        let mut period = 0;
+        let mut min = state.rep();
+
+        for n in state.cycle_iter(){
+            period += 1;
+            if n < min {
+                min = n;
+            }
+        }
+
+        if self.check_commensurability(period, state.length()){
+            Some(min)
+        } else {
+            None
+        }
+    }
+
+    fn is_rep(&self, state : &dyn State<T>) -> bool {
+        if state.bit_sum() % 2 != self.parity(){
+            return false;
+        }
+
+        let mut period = 0;
+        let rep = state.rep();
+
+        for n in state.cycle_iter(){
+            period += 1;
+            if n < rep {
+                return false;
+            }
+        }
+
+        self.check_commensurability(period, state.length())
+    }
+}
+
+impl<T> FindRepresentation<T> for EigenSpinNumMomentum
+    where T : EigenValue + LowerThan<EigenSpinNumMomentum>{
+    // state.length() is number of sites, and state.rep() holds both species (see states::hubbard)
+    fn find_rep(&self, state : &dyn State<T>) -> Option<usize> {
+        let length = state.length();
+        let (up, down) = split_species(state.rep(), length);
+        if up.count_ones() as usize != self.up_number() || down.count_ones() as usize != self.down_number(){
+            return None;
+        }
+
+        let min = FermionCycleIterator::new(state.rep(), length).ok()?.map(|(n, _s)| n).min()?;
+        let (period, sign) = fermion_period(state.rep(), length);
+
+        if self.check_fermion_commensurability(period, sign, length){
+            Some(min)
+        } else {
+            None
+        }
+    }
+
+    fn is_rep(&self, state : &dyn State<T>) -> bool {
+        match self.find_rep(state){
+            Some(min) => min == state.rep(),
+            None => false,
+        }
+    }
+}
+
 // impl<T : EigenValue> FindRepresentation<T> for EigenState<T>{
 //     fn find_rep(&self) -> Self {
 //         let eig_v = self.index.get_eigenvalue();
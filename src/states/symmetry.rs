@@ -90,8 +90,17 @@ pub trait TranslationalSymmetry : EigenValue{
     fn phase_factor(&self, length : usize) -> Complex64;
 
     fn check_commensurability(&self, period : usize, length : usize) -> bool;
+
+    fn is_real_phase(&self, length : usize) -> bool{
+        // k = 0 and k = L/2 have phase factors ±1, so the symmetrized matrix stays real
+        (2 * self.wave_number()) % length == 0
+    }
 }
 
+// Translation without a fermionic sign. The generic sector builders are bounded on it,
+// so EigenSpinNumMomentum sectors have to use hubbard_sector_hamiltonian.
+pub trait BosonicTranslation : TranslationalSymmetry{}
+
 
 #[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
 pub struct EigenNumMomentum(pub usize,pub usize);
@@ -127,6 +136,8 @@ impl TranslationalSymmetry for EigenNumMomentum{
     }
 }
 
+impl BosonicTranslation for EigenNumMomentum{}
+
 impl<T> LowerThan<T> for EigenNumMomentum where T : EigenValue + NumberConservation + TranslationalSymmetry {
     fn check_extensible(&self, other : &T) -> bool {
         (self.total_number() == other.total_number())
@@ -134,3 +145,132 @@ impl<T> LowerThan<T> for EigenNumMomentum where T : EigenValue + NumberConservat
     }
 }
 
+
+// =====================================================================================================
+// =====================================================================================================
+
+
+pub trait ParityConservation : EigenValue{
+    fn parity(&self) -> usize;
+}
+
+impl ParityConservation for EigenNumber{
+    fn parity(&self) -> usize{
+        self.0 % 2
+    }
+}
+
+impl ParityConservation for EigenNumMomentum{
+    fn parity(&self) -> usize{
+        self.0 % 2
+    }
+}
+
+
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct EigenParityMomentum(pub usize, pub usize);
+impl EigenValue for EigenParityMomentum {}
+
+impl EigenParityMomentum{
+    pub fn new(p : usize, k : usize) -> Self{
+        // p : parity of total up-spin number (0 for even, 1 for odd)
+        EigenParityMomentum(p % 2, k)
+    }
+}
+
+impl ParityConservation for EigenParityMomentum{
+    fn parity(&self) -> usize{
+        self.0
+    }
+}
+
+impl TranslationalSymmetry for EigenParityMomentum{
+    fn wave_number(&self) -> usize{
+        self.1
+    }
+
+    fn phase_factor(&self, length : usize) -> Complex64{
+        Complex64::new(0f64, 2f64 * PI * (self.1 as f64) / (length as f64)).exp()
+    }
+
+    fn check_commensurability(&self, period : usize, length : usize) -> bool{
+        (self.1 * period) % length == 0
+    }
+}
+
+impl BosonicTranslation for EigenParityMomentum{}
+
+impl<T> LowerThan<T> for EigenParityMomentum where T : EigenValue + ParityConservation + TranslationalSymmetry {
+    fn check_extensible(&self, other : &T) -> bool {
+        (self.parity() == other.parity())
+        && (self.wave_number() == other.wave_number())
+    }
+}
+
+// =====================================================================================================
+// =====================================================================================================
+
+
+pub trait SpinNumberConservation : EigenValue{
+    fn up_number(&self) -> usize;
+    fn down_number(&self) -> usize;
+}
+
+
+#[derive(Copy, Clone, Debug, Hash, Eq, Ord, PartialEq, PartialOrd)]
+pub struct EigenSpinNumMomentum(pub usize, pub usize, pub usize);
+impl EigenValue for EigenSpinNumMomentum {}
+
+impl EigenSpinNumMomentum{
+    pub fn new(n_up : usize, n_down : usize, k : usize) -> Self{
+        EigenSpinNumMomentum(n_up, n_down, k)
+    }
+
+    pub fn check_fermion_commensurability(&self, period : usize, sign : f64, length : usize) -> bool{
+        // T^period |rep> = sign |rep>, so e^{-ik period} sign should be 1
+        if sign > 0f64{
+            (self.2 * period) % length == 0
+        } else {
+            (2 * self.2 * period) % (2 * length) == length
+        }
+    }
+}
+
+impl SpinNumberConservation for EigenSpinNumMomentum{
+    fn up_number(&self) -> usize{
+        self.0
+    }
+
+    fn down_number(&self) -> usize{
+        self.1
+    }
+}
+
+impl NumberConservation for EigenSpinNumMomentum{
+    fn total_number(&self) -> usize {
+        self.0 + self.1
+    }
+}
+
+impl TranslationalSymmetry for EigenSpinNumMomentum{
+    fn wave_number(&self) -> usize{
+        self.2
+    }
+
+    fn phase_factor(&self, length : usize) -> Complex64{
+        Complex64::new(0f64, 2f64 * PI * (self.2 as f64) / (length as f64)).exp()
+    }
+
+    fn check_commensurability(&self, period : usize, length : usize) -> bool{
+        // bosonic condition, see check_fermion_commensurability for states with odd translation sign
+        (self.2 * period) % length == 0
+    }
+}
+
+impl<T> LowerThan<T> for EigenSpinNumMomentum where T : EigenValue + SpinNumberConservation + TranslationalSymmetry {
+    fn check_extensible(&self, other : &T) -> bool {
+        (self.up_number() == other.up_number())
+        && (self.down_number() == other.down_number())
+        && (self.wave_number() == other.wave_number())
+    }
+}
@@ -0,0 +1,239 @@
+use rand::Rng;
+use crate::prelude::*;
+use crate::dynamics::krylov::{lanczos, tridiagonal_eigh};
+use super::{Estimate, random_state, jackknife};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LanczosMethod{
+    // <r|exp(-beta H) A|r>
+    Finite,
+    // <r|exp(-beta H / 2) A exp(-beta H / 2)|r>, exact at zero temperature
+    LowTemperature,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanczosSample{
+    // Ritz values of the Lanczos run started from a random vector |r>
+    pub ritz : Array1<f64>,
+    // |<r|psi_j>|^2
+    pub weights : Array1<f64>,
+    // Re <r|psi_j><psi_j|A|psi_l><psi_l|r> for each observable A
+    pub observables : Vec<Array2<f64>>,
+}
+
+#[derive(Clone, Debug)]
+struct SectorSamples{
+    // sector dimension times multiplicity
+    dim : f64,
+    magnetization : f64,
+    samples : Vec<LanczosSample>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LanczosThermalState{
+    pub beta : f64,
+    pub log_partition : Estimate,
+    pub energy : Estimate,
+    pub specific_heat : Estimate,
+    pub entropy : Estimate,
+    // uniform susceptibility per site
+    pub susceptibility : Estimate,
+    pub observables : Vec<Estimate>,
+}
+
+#[derive(Clone, Debug)]
+pub struct FiniteTemperatureLanczos{
+    pub length : usize,
+    // number of Lanczos steps for each random vector
+    pub steps : usize,
+    // random vectors for each sector, fixed since samples are stored per random vector index
+    num_random : usize,
+    sectors : Vec<SectorSamples>,
+    num_observables : Option<usize>,
+}
+
+impl FiniteTemperatureLanczos{
+    pub fn new(length : usize, steps : usize, num_random : usize) -> Self{
+        Self{
+            length,
+            steps : steps.max(1),
+            num_random : num_random.max(1),
+            sectors : Vec::new(),
+            num_observables : None,
+        }
+    }
+
+    pub fn num_random(&self) -> usize{
+        self.num_random
+    }
+
+    pub fn num_sectors(&self) -> usize{
+        self.sectors.len()
+    }
+
+    pub fn sample<M, R>(&self, hamiltonian : &M, observables : &[&dyn MatVec], rng : &mut R) -> Result<LanczosSample, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        let dim = hamiltonian.dim();
+        if dim == 0 || observables.iter().any(|a| a.dim() != dim){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        let r = random_state(dim, rng);
+        let (basis, alpha, beta) = lanczos(hamiltonian, &r, self.steps.min(dim));
+        let (ritz, vecs) = tridiagonal_eigh(&alpha, &beta)?;
+        let s = ritz.len();
+        let weights = vecs.row(0).mapv(|x| x * x);
+
+        let mut result : Vec<Array2<f64>> = Vec::with_capacity(observables.len());
+        for a in observables.iter(){
+            // <psi_j|A|psi_l> from A in the Lanczos basis
+            let applied : Vec<Array1<Complex64>> = basis.iter().map(|v| a.matvec(v)).collect();
+            let mut projected : Array2<Complex64> = Array2::zeros((s, s));
+            for i in 0..s{
+                for k in 0..s{
+                    projected[[i, k]] = basis[i].iter().zip(applied[k].iter()).map(|(v, x)| v.conj() * x).sum();
+                }
+            }
+            let u = vecs.mapv(Complex64::from);
+            let ritz_basis = u.t().dot(&projected).dot(&u);
+            result.push(Array2::from_shape_fn((s, s), |(j, l)| (ritz_basis[[j, l]] * vecs[[0, j]] * vecs[[0, l]]).re));
+        }
+
+        Ok(LanczosSample{
+            ritz,
+            weights,
+            observables : result,
+        })
+    }
+
+    pub fn add_sector<M, R>(&mut self, hamiltonian : &M, magnetization : f64, multiplicity : usize, observables : &[&dyn MatVec], rng : &mut R) -> Result<(), Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // magnetization : S^z of the sector, multiplicity : number of equivalent sectors it stands for
+        if matches!(self.num_observables, Some(n) if n != observables.len()){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let samples = (0..self.num_random).map(|_| self.sample(hamiltonian, observables, rng)).collect::<Result<Vec<_>, Error>>()?;
+        // the observable count is fixed only by a successful sector
+        self.num_observables = Some(observables.len());
+        self.sectors.push(SectorSamples{
+            dim : (hamiltonian.dim() * multiplicity) as f64,
+            magnetization,
+            samples,
+        });
+        Ok(())
+    }
+
+    pub fn add_momentum_sector<H, R>(&mut self, egn_v : EigenNumMomentum, hamiltonian : &H, observables : &[&dyn HamiltonianTerm], rng : &mut R) -> Result<(), Error>
+        where H : HamiltonianTerm + ?Sized, R : Rng{
+        // BasisNK sector with sparse Hamiltonian and observables
+        let (basis, indices) = BasisNK::new(egn_v, self.length).build_light()?;
+        let h = sparse_sector_hamiltonian(&basis, &indices, egn_v, hamiltonian);
+        let ops : Vec<SparseMatrix> = observables.iter().map(|a| sparse_sector_hamiltonian(&basis, &indices, egn_v, *a)).collect();
+        let ops : Vec<&dyn MatVec> = ops.iter().map(|a| a as &dyn MatVec).collect();
+        let magnetization = egn_v.total_number() as f64 - (self.length as f64) / 2f64;
+        self.add_sector(&h, magnetization, 1, &ops, rng)
+    }
+
+    fn replicas(&self, beta : f64, method : LanczosMethod, e0 : f64) -> Vec<Vec<f64>>{
+        // for each random vector index : Z, E, E^2, M, M^2 and observables, Boltzmann factors shifted by E0
+        let num_obs = self.num_observables.unwrap_or(0);
+        (0..self.num_random).map(|r| {
+            let mut moments = vec![0f64; 5 + num_obs];
+            for sector in self.sectors.iter(){
+                let sample = &sector.samples[r];
+                let boltzmann = sample.ritz.mapv(|e| (-beta * (e - e0)).exp());
+                let (m, d) = (sector.magnetization, sector.dim);
+                for j in 0..sample.ritz.len(){
+                    let x = d * sample.weights[j] * boltzmann[j];
+                    let de = sample.ritz[j] - e0;
+                    moments[0] += x;
+                    moments[1] += x * de;
+                    moments[2] += x * de * de;
+                    moments[3] += x * m;
+                    moments[4] += x * m * m;
+                }
+                for (a, c) in sample.observables.iter().enumerate(){
+                    moments[5 + a] += d * match method{
+                        LanczosMethod::Finite => c.indexed_iter().map(|((j, _), &x)| x * boltzmann[j]).sum::<f64>(),
+                        LanczosMethod::LowTemperature => c.indexed_iter().map(|((j, l), &x)| x * (boltzmann[j] * boltzmann[l]).sqrt()).sum::<f64>(),
+                    };
+                }
+            }
+            moments
+        }).collect()
+    }
+
+    pub fn at(&self, beta : f64, method : LanczosMethod) -> Result<LanczosThermalState, Error>{
+        if self.sectors.is_empty(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let e0 = self.sectors.iter()
+                    .flat_map(|s| s.samples.iter().flat_map(|x| x.ritz.iter().cloned()))
+                    .fold(f64::INFINITY, f64::min);
+        let replicas = self.replicas(beta, method, e0);
+        let length = self.length as f64;
+
+        Ok(LanczosThermalState{
+            beta,
+            log_partition : jackknife(&replicas, |x| x[0].ln() - beta * e0),
+            energy : jackknife(&replicas, |x| e0 + x[1] / x[0]),
+            specific_heat : jackknife(&replicas, |x| beta * beta * (x[2] / x[0] - (x[1] / x[0]).powi(2))),
+            entropy : jackknife(&replicas, |x| x[0].ln() + beta * x[1] / x[0]),
+            susceptibility : jackknife(&replicas, |x| beta * (x[4] / x[0] - (x[3] / x[0]).powi(2)) / length),
+            observables : (0..self.num_observables.unwrap_or(0)).map(|a| jackknife(&replicas, |x| x[5 + a] / x[0])).collect(),
+        })
+    }
+
+    pub fn scan(&self, betas : &[f64], method : LanczosMethod) -> Result<Vec<LanczosThermalState>, Error>{
+        betas.iter().map(|&b| self.at(b, method)).collect()
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_ftlm(){
+        let length = 8;
+        let xxz = PeriodicNearestXXZ::new(1f64, 2f64);
+        let mut rng = rng_seed(1234);
+
+        let mut ftlm = FiniteTemperatureLanczos::new(length, 40, 20);
+        let spectra = sector_spectra(length, &xxz).unwrap();
+        for &egn_v in spectra.keys(){
+            ftlm.add_momentum_sector(egn_v, &xxz, &[&xxz], &mut rng).unwrap();
+        }
+        assert_eq!(ftlm.num_sectors(), spectra.len());
+        let exact = ThermalSpectrum::from_sectors(&spectra, length).unwrap();
+
+        let beta = 0.5;
+        let truth = exact.at(beta);
+        for &method in [LanczosMethod::Finite, LanczosMethod::LowTemperature].iter(){
+            let state = ftlm.at(beta, method).unwrap();
+            assert!(state.energy.error > 0f64);
+            assert!((state.energy.value - truth.energy).abs() < 5f64 * state.energy.error);
+            assert!((state.log_partition.value - truth.log_partition).abs() < 5f64 * state.log_partition.error);
+            // every sector fits in the Krylov space, so <H> agrees with the energy estimate
+            assert!((state.observables[0].value - state.energy.value).abs() < 1e-8);
+        }
+
+        // ferromagnetic ground states with m = 0 and m = L are one dimensional sectors
+        let cold = ftlm.at(10f64, LanczosMethod::LowTemperature).unwrap();
+        assert!((cold.energy.value + 8f64).abs() < 1e-4);
+        assert!((cold.entropy.value - 2f64.ln()).abs() < 1e-4);
+
+        let mut other = FiniteTemperatureLanczos::new(length, 10, 2);
+        assert_eq!(other.num_random(), 2);
+        let egn_v = EigenNumMomentum::new(4, 0);
+        let (basis, indices) = BasisNK::new(egn_v, length).build_light().unwrap();
+        let h = SectorMatrix::build(&basis, &indices, egn_v, &xxz);
+        // a failed sector does not fix the number of observables
+        let wrong : Array2<Complex64> = Array2::zeros((1, 1));
+        assert!(other.add_sector(&h, 0f64, 1, &[&wrong], &mut rng).is_err());
+        assert_eq!(other.num_sectors(), 0);
+        other.add_sector(&h, 0f64, 1, &[], &mut rng).unwrap();
+        assert!(other.add_sector(&h, 0f64, 1, &[&h], &mut rng).is_err());
+    }
+}
@@ -0,0 +1,66 @@
+use rand::Rng;
+use crate::prelude::*;
+
+pub mod ftlm;
+pub mod tpq;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Estimate{
+    pub value : f64,
+    // jackknife standard error over random vectors
+    pub error : f64,
+}
+
+pub fn random_state<R : Rng>(dim : usize, rng : &mut R) -> Array1<Complex64>{
+    // normalized vector with independent complex gaussian components, uniform on the unit sphere
+    let psi : Array1<Complex64> = (0..dim).map(|_| Complex64::new(gaussian(rng), gaussian(rng))).collect();
+    let norm = psi.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+    psi / Complex64::from(norm)
+}
+
+pub(crate) fn jackknife<F>(replicas : &[Vec<f64>], f : F) -> Estimate
+    where F : Fn(&[f64]) -> f64{
+    // f of the replica mean, with the error from leave-one-out means
+    let n = replicas.len();
+    let width = replicas[0].len();
+    let total : Vec<f64> = (0..width).map(|i| replicas.iter().map(|x| x[i]).sum()).collect();
+    let value = f(&total.iter().map(|x| x / (n as f64)).collect::<Vec<f64>>());
+    if n < 2{
+        return Estimate{ value, error : 0f64 };
+    }
+
+    let partial : Vec<f64> = replicas.iter().map(|x| {
+        let mean : Vec<f64> = (0..width).map(|i| (total[i] - x[i]) / ((n - 1) as f64)).collect();
+        f(&mean)
+    }).collect();
+    let mean = partial.iter().sum::<f64>() / (n as f64);
+    let var = partial.iter().map(|x| (x - mean).powi(2)).sum::<f64>() * ((n - 1) as f64) / (n as f64);
+    return Estimate{ value, error : var.sqrt() };
+}
+
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_random_state(){
+        let psi1 = random_state(50, &mut rng_seed(42));
+        let psi2 = random_state(50, &mut rng_seed(42));
+        assert_eq!(psi1, psi2);
+        assert!((psi1.iter().map(|x| x.norm_sqr()).sum::<f64>() - 1f64).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_jackknife(){
+        // linear functions give the standard error of the mean
+        let replicas = vec![vec![1.0], vec![2.0], vec![3.0], vec![6.0]];
+        let est = jackknife(&replicas, |x| x[0]);
+        assert!((est.value - 3.0).abs() < 1e-12);
+        assert!((est.error - (14f64 / 12f64).sqrt()).abs() < 1e-12);
+
+        let ratio = jackknife(&[vec![2.0, 1.0], vec![4.0, 2.0]], |x| x[0] / x[1]);
+        assert!((ratio.value - 2.0).abs() < 1e-12);
+        assert!(ratio.error.abs() < 1e-12);
+    }
+}
@@ -0,0 +1,248 @@
+use rand::Rng;
+use crate::prelude::*;
+use crate::dynamics::krylov::{lanczos, tridiagonal_eigh};
+use super::random_state;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TPQPoint{
+    pub beta : f64,
+    pub energy : f64,
+    // <H^2> - <H>^2, beta^2 times it is the specific heat of canonical states
+    pub energy_variance : f64,
+    // ln(D <beta|beta>), only for canonical states
+    pub log_partition : Option<f64>,
+    pub observables : Vec<f64>,
+}
+
+fn expectation(psi : &Array1<Complex64>, applied : &Array1<Complex64>) -> f64{
+    psi.iter().zip(applied.iter()).map(|(v, x)| v.conj() * x).sum::<Complex64>().re
+}
+
+fn measure<M>(hamiltonian : &M, observables : &[&dyn MatVec], psi : &Array1<Complex64>, beta : f64, log_partition : Option<f64>) -> (TPQPoint, Array1<Complex64>)
+    where M : MatVec + ?Sized{
+    // psi normalized, also returns H|psi>
+    let h_psi = hamiltonian.matvec(psi);
+    let energy = expectation(psi, &h_psi);
+    let second = h_psi.iter().map(|x| x.norm_sqr()).sum::<f64>();
+    let point = TPQPoint{
+        beta,
+        energy,
+        energy_variance : second - energy * energy,
+        log_partition,
+        observables : observables.iter().map(|a| expectation(psi, &a.matvec(psi))).collect(),
+    };
+    (point, h_psi)
+}
+
+fn check_dims<M>(hamiltonian : &M, observables : &[&dyn MatVec]) -> Result<usize, Error>
+    where M : MatVec + ?Sized{
+    let dim = hamiltonian.dim();
+    if dim == 0 || observables.iter().any(|a| a.dim() != dim){
+        return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+    }
+    Ok(dim)
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct MicrocanonicalTPQ{
+    // l has to exceed the largest energy per site
+    pub l : f64,
+    pub num_sites : usize,
+}
+
+impl MicrocanonicalTPQ{
+    pub fn new(l : f64, num_sites : usize) -> Self{
+        Self{
+            l,
+            num_sites,
+        }
+    }
+
+    pub fn run<M, R>(&self, hamiltonian : &M, observables : &[&dyn MatVec], steps : usize, rng : &mut R) -> Result<Vec<TPQPoint>, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // |psi_k> ~ (l - H/N)^k |r>, with beta_k = 2k / (N (l - u_k)) and u_k the energy per site
+        check_dims(hamiltonian, observables)?;
+        let n = self.num_sites as f64;
+        let mut psi = random_state(hamiltonian.dim(), rng);
+        let mut result = Vec::with_capacity(steps + 1);
+
+        for k in 0..=steps{
+            let (mut point, h_psi) = measure(hamiltonian, observables, &psi, 0f64, None);
+            let gap = self.l - point.energy / n;
+            if gap <= 0f64{
+                return Err(Error::make_error_msg(format!("l = {} is below the energy per site {}", self.l, point.energy / n)));
+            }
+            point.beta = 2f64 * (k as f64) / (n * gap);
+            result.push(point);
+
+            psi = &psi * Complex64::from(self.l) - h_psi / Complex64::from(n);
+            let norm = psi.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+            psi /= Complex64::from(norm);
+        }
+        return Ok(result);
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CanonicalTPQ{
+    // Lanczos dimension for each imaginary time step
+    pub krylov_dim : usize,
+    // largest increment of beta in one step
+    pub d_beta : f64,
+}
+
+impl CanonicalTPQ{
+    pub fn new(krylov_dim : usize, d_beta : f64) -> Self{
+        Self{
+            krylov_dim : krylov_dim.max(1),
+            d_beta,
+        }
+    }
+
+    fn imaginary_step<M>(&self, hamiltonian : &M, psi : &Array1<Complex64>, tau : f64) -> Result<(Array1<Complex64>, f64), Error>
+        where M : MatVec + ?Sized{
+        // exp(-tau H)|psi> normalized and ln of its norm, exponent shifted by the lowest Ritz value
+        let (basis, alpha, beta) = lanczos(hamiltonian, psi, self.krylov_dim.min(hamiltonian.dim()));
+        let (theta, vecs) = tridiagonal_eigh(&alpha, &beta)?;
+        let shift = theta[0];
+        let s = theta.len();
+        let mut result : Array1<Complex64> = Array1::zeros(hamiltonian.dim());
+        for (i, v) in basis.iter().enumerate().take(s){
+            let c : f64 = (0..s).map(|l| (-tau * (theta[l] - shift)).exp() * vecs[[i, l]] * vecs[[0, l]]).sum();
+            result = result + v * Complex64::from(c);
+        }
+        let norm = result.iter().map(|x| x.norm_sqr()).sum::<f64>().sqrt();
+        Ok((result / Complex64::from(norm), norm.ln() - tau * shift))
+    }
+
+    pub fn run<M, R>(&self, hamiltonian : &M, observables : &[&dyn MatVec], betas : &[f64], rng : &mut R) -> Result<Vec<TPQPoint>, Error>
+        where M : MatVec + ?Sized, R : Rng{
+        // |beta> = exp(-beta H / 2)|r> for ascending betas, Z is estimated by D <beta|beta>
+        let dim = check_dims(hamiltonian, observables)?;
+        if self.d_beta <= 0f64 || betas.iter().any(|&b| b < 0f64) || betas.windows(2).any(|w| w[1] < w[0]){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        let mut psi = random_state(dim, rng);
+        let mut log_norm = 0f64;
+        let mut now = 0f64;
+        let mut result = Vec::with_capacity(betas.len());
+        for &beta in betas.iter(){
+            while now < beta{
+                let db = (beta - now).min(self.d_beta);
+                let (next, log_step) = self.imaginary_step(hamiltonian, &psi, db / 2f64)?;
+                psi = next;
+                // <beta|beta> gains the square of the norm
+                log_norm += 2f64 * log_step;
+                now += db;
+            }
+            let (point, _) = measure(hamiltonian, observables, &psi, beta, Some((dim as f64).ln() + log_norm));
+            result.push(point);
+        }
+        return Ok(result);
+    }
+
+    pub fn combine(runs : &[Vec<Vec<TPQPoint>>]) -> Result<Vec<TPQPoint>, Error>{
+        // runs[sector][sample] at common betas. Z is the sample mean within a sector summed over sectors,
+        // expectation values are weighted by the estimated partition function of each run.
+        let points : Vec<(f64, &Vec<TPQPoint>)> = runs.iter()
+                        .flat_map(|sector| sector.iter().map(move |run| (1f64 / sector.len() as f64, run)))
+                        .collect();
+        if points.is_empty(){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+        let len = points[0].1.len();
+        if points.iter().any(|(_, run)| run.len() != len || run.iter().any(|p| p.log_partition.is_none())){
+            return Err(Error::make_error_syntax(ErrorCode::InvalidArgument));
+        }
+
+        let mut result = Vec::with_capacity(len);
+        for i in 0..len{
+            let logs : Vec<f64> = points.iter().map(|(w, run)| w.ln() + run[i].log_partition.unwrap()).collect();
+            let max = logs.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+            let weights : Vec<f64> = logs.iter().map(|x| (x - max).exp()).collect();
+            let total : f64 = weights.iter().sum();
+            let average = |f : &dyn Fn(&TPQPoint) -> f64| points.iter().zip(weights.iter()).map(|((_, run), w)| w * f(&run[i])).sum::<f64>() / total;
+
+            let energy = average(&|p| p.energy);
+            let second = average(&|p| p.energy_variance + p.energy * p.energy);
+            let num_obs = points[0].1[i].observables.len();
+            result.push(TPQPoint{
+                beta : points[0].1[i].beta,
+                energy,
+                energy_variance : second - energy * energy,
+                log_partition : Some(max + total.ln()),
+                observables : (0..num_obs).map(|a| average(&|p| p.observables[a])).collect(),
+            });
+        }
+        return Ok(result);
+    }
+}
+
+
+#[cfg(test)]
+mod test {
+    use crate::hamiltonian::sparse::xxz_test_sector;
+    use super::*;
+
+    #[test]
+    fn test_microcanonical_tpq(){
+        let (h, eval, evec) = xxz_test_sector(EigenNumMomentum::new(5, 0), 10);
+        let num_sites = 10;
+        // every bond is bounded by delta_x + delta_z / 2
+        let l = 3f64;
+        let points = MicrocanonicalTPQ::new(l, num_sites).run(&h, &[&h], 50, &mut rng_seed(7)).unwrap();
+        assert_eq!(points.len(), 51);
+        assert_eq!(points[0].beta, 0f64);
+
+        // exact moments of (l - H/N)^{2k} in the random state
+        let r = random_state(h.dim(), &mut rng_seed(7));
+        let evec_h : Array2<Complex64> = conjugate(&evec);
+        let overlap = evec_h.dot(&r);
+        let p = overlap.mapv(|x| x.norm_sqr());
+        for (k, point) in points.iter().enumerate(){
+            let f = eval.mapv(|e| (l - e / (num_sites as f64)).powi(2 * k as i32));
+            let z = (&p * &f).sum();
+            let energy = (&p * &f * &eval).sum() / z;
+            assert!((point.energy - energy).abs() < 1e-8);
+            assert!((point.observables[0] - point.energy).abs() < 1e-10);
+            if k > 0{
+                assert!(point.energy <= points[k - 1].energy + 1e-12);
+                assert!(point.beta > 0f64);
+            }
+        }
+        assert!(MicrocanonicalTPQ::new(-10f64, num_sites).run(&h, &[], 1, &mut rng_seed(7)).is_err());
+    }
+
+    #[test]
+    fn test_canonical_tpq(){
+        let (h, eval, evec) = xxz_test_sector(EigenNumMomentum::new(5, 0), 10);
+        let betas = [0.0, 0.3, 1.0, 2.0];
+        let ctpq = CanonicalTPQ::new(20, 0.1);
+        let points = ctpq.run(&h, &[&h], &betas, &mut rng_seed(11)).unwrap();
+
+        let r = random_state(h.dim(), &mut rng_seed(11));
+        let evec_h : Array2<Complex64> = conjugate(&evec);
+        let overlap = evec_h.dot(&r);
+        let p = overlap.mapv(|x| x.norm_sqr());
+        for (point, &beta) in points.iter().zip(betas.iter()){
+            let f = eval.mapv(|e| (-beta * e).exp());
+            let z = (&p * &f).sum();
+            let energy = (&p * &f * &eval).sum() / z;
+            let second = (&p * &f * &eval * &eval).sum() / z;
+            assert!((point.energy - energy).abs() < 1e-8);
+            assert!((point.energy_variance - (second - energy * energy)).abs() < 1e-8);
+            assert!((point.log_partition.unwrap() - ((h.dim() as f64) * z).ln()).abs() < 1e-8);
+        }
+
+        // combining identical runs changes nothing, and a sector twice doubles Z
+        let combined = CanonicalTPQ::combine(&[vec![points.clone(), points.clone()]]).unwrap();
+        let doubled = CanonicalTPQ::combine(&[vec![points.clone()], vec![points.clone()]]).unwrap();
+        for i in 0..betas.len(){
+            assert!((combined[i].energy - points[i].energy).abs() < 1e-12);
+            assert!((combined[i].energy_variance - points[i].energy_variance).abs() < 1e-10);
+            assert!((doubled[i].log_partition.unwrap() - points[i].log_partition.unwrap() - 2f64.ln()).abs() < 1e-12);
+        }
+        assert!(ctpq.run(&h, &[], &[1.0, 0.5], &mut rng_seed(11)).is_err());
+    }
+}
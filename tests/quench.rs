@@ -147,3 +147,30 @@ fn test_quench() -> (){
     close_l2(&change, &truth_change, rtol);
 
 }
+
+#[test]
+fn test_quench_protocol() -> (){
+    // same numbers as test_quench through the Quench abstraction
+    let l = 4;
+    let m = 2;
+    let k = 0;
+    let delta  = 2f64;
+    let lambda = 1f64;
+    let rtol = 1e-6;
+
+    let egn_v = EigenNumMomentum::new(m, k);
+    let (basis, indices) = BasisNK::new(egn_v, l).build_light().unwrap();
+    let h0 = SectorMatrix::build(&basis, &indices, egn_v, &PeriodicNearestXXZ::new(1f64, delta));
+    let h1 = SectorMatrix::build(&basis, &indices, egn_v, &PeriodicIsing::new(lambda));
+
+    let mut rng = rng_seed(124178297891748914u128);
+    let uni = Uniform::new(-1f64, 1f64);
+    let r = rng.sample(uni);
+
+    let quench = Quench::new(h0, h1, QuenchForm::Evolve(1f64)).unwrap();
+    let truth_change : Array1<f64> = arr1(&[0.0007045192755643637, -0.0007045192755645857]);
+    close_l2(&quench.energy_change(r).unwrap(), &truth_change, rtol);
+
+    let matrix = quench.energy_matrix(r).unwrap();
+    close_l2(&matrix.diag().map(|x| x.re), &(truth_change + &quench.eval0), rtol);
+}